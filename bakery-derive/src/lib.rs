@@ -1,13 +1,29 @@
 extern crate proc_macro2;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::{
-    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields, GenericParam,
-    Generics,
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput,
+    Expr, ExprLit, ExprPath, ExprUnary, Field, Fields, FieldsNamed, GenericParam, Generics, Lit,
+    Meta, NestedMeta, UnOp,
 };
 
 /// Implements bakery::Recipe trait for the derived type
-#[proc_macro_derive(Recipe)]
+///
+/// `#[serde(...)]` attributes are honored so the recipe matches the `Deserialize`
+/// implementation: `rename`, `rename_all`, `skip`, `skip_deserializing` and `default`. The same
+/// options can be given with `#[bakery(...)]` attributes, which take precedence over serde ones.
+/// Serde options which change the encoding in other ways, such as `with`, `flatten` or `tag`, are
+/// rejected, as is `skip` on the field of a newtype.
+///
+/// Default values are serialized with bincode when the recipe is built, and written in place of
+/// omitted members, so `#[bakery(default)]` works on its own. Members with a default must
+/// implement `Serialize`, and `Default` unless a function is given.
+///
+/// Enumerations are encoded with their variant index on 32 bits, as serde and bincode do.
+/// Explicit discriminants must therefore match the variant index. Enumerations with only unit
+/// variants and a `#[repr]` attribute can opt in with `#[bakery(repr)]` to be encoded with their
+/// discriminant instead, stored as the repr type, which is what serde_repr expects.
+#[proc_macro_derive(Recipe, attributes(bakery, serde))]
 pub fn derive_bakery(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // This code is derived from the heapsize_derive example from the syn crate:
     // https://github.com/dtolnay/syn/blob/master/examples/heapsize/heapsize_derive/src/lib.rs
@@ -17,31 +33,18 @@ pub fn derive_bakery(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     let generics = add_trait_bounds(input.generics);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let fields = recipe_token_stream(&input.data);
-
-    let implementation = match input.data {
-        Data::Struct(_) => {
-            quote! {
-                let nid = tree.create_struct(None, "S");
-                #fields
-                nid
-            }
-        }
-        Data::Enum(_) => {
-            quote! {
-                let nid_storage_ty = i32::recipe(tree);
-                let nid = tree.create_enum(None, "E", nid_storage_ty);
-                let mut next_enum_value = 0;
-                #fields
-                nid
-            }
-        }
-        Data::Union(_) => unimplemented!(),
+    let implementation = match recipe_implementation(&input.attrs, &input.data) {
+        Ok(implementation) => implementation,
+        Err(e) => return e.to_compile_error().into(),
     };
 
     let expanded = quote! {
         impl #impl_generics bakery::Recipe for #name #ty_generics #where_clause {
             fn recipe(tree: &mut bakery::NodeTree) -> u32 {
+                // The type may already be built, or under construction for recursive types.
+                if let Some(nid) = tree.registered_type::<Self>() {
+                    return tree.create_type_ref(nid);
+                }
                 #implementation
             }
         }
@@ -50,84 +53,586 @@ pub fn derive_bakery(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     proc_macro::TokenStream::from(expanded)
 }
 
-// Add a bound `T: bakery::Recipe` to every type parameter T
+// Add a bound `T: bakery::Recipe + 'static` to every type parameter T. Derived types are
+// registered by their `TypeId`, which requires them to be `'static`.
 fn add_trait_bounds(mut generics: Generics) -> Generics {
     for param in &mut generics.params {
         if let GenericParam::Type(ref mut type_param) = *param {
             type_param.bounds.push(parse_quote!(bakery::Recipe));
+            type_param.bounds.push(parse_quote!('static));
         }
     }
     generics
 }
 
-fn recipe_token_stream(data: &Data) -> TokenStream {
-    match *data {
-        Data::Struct(ref data) => {
-            match data.fields {
-                Fields::Named(ref fields) => {
-                    let quotes = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let ty = &f.ty;
-                        quote_spanned! {
-                            f.span() =>
-                                // To comply with borrow checker, those two lines cannot be merged.
-                                let nid_ty = <#ty> :: recipe(tree);
-                                tree.create_struct_member(nid, stringify!(#name), nid_ty);
+/// Default value of a structure member
+enum DefaultValue {
+    /// `Default::default()` of the member type
+    Trait,
+    /// Value returned by the given function
+    Path(ExprPath),
+}
+
+/// Serde options which do not change how a type is deserialized from bincode
+const SERDE_IGNORED_OPTIONS: &[&str] = &[
+    "alias",
+    "borrow",
+    "bound",
+    "crate",
+    "deny_unknown_fields",
+    "expecting",
+    "into",
+    "serialize_with",
+    "skip_serializing",
+    "skip_serializing_if",
+];
+
+/// Options given by `#[serde(...)]` and `#[bakery(...)]` attributes of a container, variant or
+/// field.
+#[derive(Default)]
+struct Attrs {
+    rename: Option<String>,
+    rename_all: Option<RenameRule>,
+    skip: bool,
+    default: Option<DefaultValue>,
+    /// Encode enumeration discriminants as the `#[repr]` type
+    repr: bool,
+}
+
+impl Attrs {
+    /// Parse the options from the attributes of an item. Serde attributes are parsed first, so
+    /// bakery attributes override them.
+    ///
+    /// Serde options which do not change the binary encoding are ignored. Other unknown serde
+    /// options are errors, since the recipe would not match the `Deserialize` implementation.
+    /// Unknown bakery options are errors as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `attrs` - Attributes of the container, variant or field
+    fn parse(attrs: &[Attribute]) -> syn::Result<Attrs> {
+        let mut result = Attrs::default();
+        // Conversions are only supported for enumerations encoded with their discriminant.
+        let mut conversion = None;
+        for strict in [false, true] {
+            let ident = if strict { "bakery" } else { "serde" };
+            for attr in attrs.iter().filter(|attr| attr.path.is_ident(ident)) {
+                let list = match attr.parse_meta()? {
+                    Meta::List(list) => list,
+                    meta => return Err(syn::Error::new(meta.span(), "expected a list of options")),
+                };
+                for nested in list.nested {
+                    let meta = match nested {
+                        NestedMeta::Meta(meta) => meta,
+                        NestedMeta::Lit(lit) => {
+                            return Err(syn::Error::new(lit.span(), "expected an option"))
                         }
-                    });
-                    quote! {
-                        #( #quotes )*
+                    };
+                    if result.parse_option(&meta)? {
+                        continue;
+                    }
+                    if strict {
+                        return Err(syn::Error::new(meta.span(), "unknown bakery option"));
+                    }
+                    let path = meta.path();
+                    if path.is_ident("from") || path.is_ident("try_from") {
+                        conversion = Some(meta.span());
+                    } else if !SERDE_IGNORED_OPTIONS
+                        .iter()
+                        .any(|option| path.is_ident(option))
+                    {
+                        return Err(syn::Error::new(
+                            meta.span(),
+                            "unsupported serde option, the recipe would not match the \
+                            Deserialize implementation",
+                        ));
+                    }
+                }
+            }
+        }
+        match conversion {
+            Some(span) if !result.repr => Err(syn::Error::new(
+                span,
+                "serde conversions require a #[bakery(repr)] enumeration",
+            )),
+            _ => Ok(result),
+        }
+    }
+
+    /// Parse a single option, return false if the option is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `meta` - Option to be parsed
+    fn parse_option(&mut self, meta: &Meta) -> syn::Result<bool> {
+        let path = meta.path();
+        if path.is_ident("rename") {
+            if let Some(s) = deserialize_name(meta)? {
+                self.rename = Some(s.value());
+            }
+        } else if path.is_ident("rename_all") {
+            if let Some(s) = deserialize_name(meta)? {
+                self.rename_all = Some(RenameRule::from_str(&s.value()).ok_or_else(|| {
+                    syn::Error::new(s.span(), format!("unknown rename rule \"{}\"", s.value()))
+                })?);
+            }
+        } else if path.is_ident("skip") || path.is_ident("skip_deserializing") {
+            self.skip = true;
+        } else if path.is_ident("repr") {
+            self.repr = true;
+        } else if path.is_ident("default") {
+            self.default = Some(match meta {
+                Meta::Path(_) => DefaultValue::Trait,
+                Meta::NameValue(nv) => match &nv.lit {
+                    Lit::Str(s) => DefaultValue::Path(s.parse()?),
+                    lit => return Err(syn::Error::new(lit.span(), "expected a function path")),
+                },
+                Meta::List(_) => {
+                    return Err(syn::Error::new(meta.span(), "expected a function path"))
+                }
+            });
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+/// Return the name used for deserialization by a `rename` or `rename_all` option, which is
+/// either `rename = "x"` or `rename(deserialize = "x")`. Return None if only the serialization
+/// name is given.
+///
+/// # Arguments
+///
+/// * `meta` - Option to be parsed
+fn deserialize_name(meta: &Meta) -> syn::Result<Option<syn::LitStr>> {
+    match meta {
+        Meta::NameValue(nv) => match &nv.lit {
+            Lit::Str(s) => Ok(Some(s.clone())),
+            lit => Err(syn::Error::new(lit.span(), "expected a string")),
+        },
+        Meta::List(list) => {
+            for nested in &list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident("deserialize") {
+                        if let Lit::Str(s) = &nv.lit {
+                            return Ok(Some(s.clone()));
+                        }
+                    }
+                }
+            }
+            Ok(None)
+        }
+        Meta::Path(_) => Err(syn::Error::new(meta.span(), "expected a name")),
+    }
+}
+
+/// Case conversion applied by `rename_all`, with the same rules as serde.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<RenameRule> {
+        match rule {
+            "lowercase" => Some(RenameRule::Lower),
+            "UPPERCASE" => Some(RenameRule::Upper),
+            "PascalCase" => Some(RenameRule::Pascal),
+            "camelCase" => Some(RenameRule::Camel),
+            "snake_case" => Some(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(RenameRule::ScreamingSnake),
+            "kebab-case" => Some(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Some(RenameRule::ScreamingKebab),
+            _ => None,
+        }
+    }
+
+    /// Rename a field, which is expected to be in snake case.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply_to_field(field);
+                pascal[..1].to_ascii_lowercase() + &pascal[1..]
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Rename a variant, which is expected to be in Pascal case.
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => variant[..1].to_ascii_lowercase() + &variant[1..],
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
                     }
+                    snake.push(c.to_ascii_lowercase());
                 }
-                Fields::Unnamed(_) | Fields::Unit => unimplemented!(),
+                snake
             }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
         }
+    }
+}
+
+/// Return the body of the `recipe` function.
+///
+/// # Arguments
+///
+/// * `attrs` - Attributes of the derived type
+/// * `data` - Derived type
+fn recipe_implementation(attrs: &[Attribute], data: &Data) -> syn::Result<TokenStream> {
+    let container = Attrs::parse(attrs)?;
+    Ok(match *data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => {
+                // With a container default, missing members take their value from the default
+                // of the whole structure.
+                let default = match container.default {
+                    Some(DefaultValue::Trait) => Some(quote! {
+                        let __default = <Self as ::core::default::Default>::default();
+                    }),
+                    Some(DefaultValue::Path(ref path)) => Some(quote! {
+                        let __default: Self = #path();
+                    }),
+                    None => None,
+                };
+                let members = struct_members(fields, quote!(nid), &container, default.is_some())?;
+                quote! {
+                    let nid = tree.create_struct(None, "S");
+                    tree.register_type::<Self>(nid);
+                    #default
+                    #members
+                    nid
+                }
+            }
+            // Newtypes are serialized as their inner value. They still have a node of their own,
+            // registered before the inner type is built, so that they can be recursive.
+            Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+                reject_newtype_skip(&unnamed.unnamed[0])?;
+                let ty = &unnamed.unnamed[0].ty;
+                quote! {
+                    let nid = tree.create_newtype(None);
+                    tree.register_type::<Self>(nid);
+                    let nid_ty = <#ty> :: recipe(tree);
+                    tree.set_newtype_content(nid, nid_ty);
+                    nid
+                }
+            }
+            Fields::Unnamed(ref fields) if !fields.unnamed.is_empty() => {
+                let mut quotes = Vec::new();
+                for f in &fields.unnamed {
+                    if Attrs::parse(&f.attrs)?.skip {
+                        continue;
+                    }
+                    let ty = &f.ty;
+                    quotes.push(quote_spanned! {
+                        f.span() =>
+                            let nid_ty = <#ty> :: recipe(tree);
+                            tree.create_tuple_member(nid, nid_ty);
+                    });
+                }
+                quote! {
+                    let nid = tree.create_tuple(None);
+                    tree.register_type::<Self>(nid);
+                    #( #quotes )*
+                    nid
+                }
+            }
+            // Unit structs are serialized as nothing, like empty structures.
+            Fields::Unnamed(_) | Fields::Unit => {
+                quote! {
+                    tree.create_struct(None, "S")
+                }
+            }
+        },
         Data::Enum(ref data) => {
-            let quotes_variant = data.variants.iter().map(|variant| {
-                let name = &    variant.ident;
+            // Serde encodes variants with their index, which bincode writes as an u32. Unit-only
+            // enumerations can opt in to be encoded with their discriminant, stored as the repr
+            // type, as serde_repr does.
+            let unit_only = data
+                .variants
+                .iter()
+                .all(|v| matches!(v.fields, Fields::Unit));
+            let repr = match (container.repr, enum_repr(attrs)?) {
+                (false, _) => None,
+                (true, Some(_)) if !unit_only => {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        "#[bakery(repr)] requires an enumeration with only unit variants",
+                    ))
+                }
+                (true, Some(repr)) => Some(repr),
+                (true, None) => {
+                    return Err(syn::Error::new(
+                        Span::call_site(),
+                        "#[bakery(repr)] requires a #[repr] attribute with an integer type",
+                    ))
+                }
+            };
+            let storage = match &repr {
+                Some(repr) => quote!(<#repr>::recipe(tree)),
+                None => quote!(u32::recipe(tree)),
+            };
+            let mut quotes_variant = Vec::new();
+            // Serde variant index, and discriminant of the next variant as Rust counts it.
+            let mut index = 0;
+            let mut next_discriminant = 0;
+            for variant in &data.variants {
+                let variant_attrs = Attrs::parse(&variant.attrs)?;
+                let value = match &variant.discriminant {
+                    Some((_, expr)) => Some((discriminant_value(expr)?, expr)),
+                    None => None,
+                };
+                let value = match value {
+                    // All values are given explicitly, so skipped variants are still counted.
+                    _ if repr.is_some() => {
+                        let discriminant = value.map_or(next_discriminant, |(value, _)| value);
+                        next_discriminant = discriminant + 1;
+                        let discriminant = proc_macro2::Literal::i128_suffixed(discriminant);
+                        quote!(Some(::core::convert::From::from(#discriminant)))
+                    }
+                    Some((value, expr)) if value != index && !variant_attrs.skip => {
+                        let hint = match enum_repr(attrs)? {
+                            _ if !unit_only => "",
+                            Some(_) => ", add a #[bakery(repr)] attribute to encode discriminants",
+                            None => {
+                                ", add #[repr] and #[bakery(repr)] attributes to encode \
+                                discriminants"
+                            }
+                        };
+                        return Err(syn::Error::new(
+                            expr.span(),
+                            format!(
+                                "discriminant does not match the variant index {} encoded by \
+                                serde{}",
+                                index, hint
+                            ),
+                        ));
+                    }
+                    _ => quote!(None),
+                };
+                // Skipped variants cannot be deserialized, and serde does not count them when
+                // numbering the variants.
+                if variant_attrs.skip {
+                    continue;
+                }
+                index += 1;
+                let name = match (&variant_attrs.rename, container.rename_all) {
+                    (Some(rename), _) => rename.clone(),
+                    (None, Some(rule)) => rule.apply_to_variant(&variant.ident.unraw().to_string()),
+                    (None, None) => variant.ident.unraw().to_string(),
+                };
                 let quote_fields = match variant.fields {
                     Fields::Named(ref fields) => {
                         // This enumeration has named values
                         // We create a `RecStruct` node as a child of a `RecEnumItem` node.
-                        let quotes_fields = fields.named.iter().map(|f| {
-                            let name = &f.ident;
-                            let ty = &f.ty;
-                            quote! {
-                                let nid_ty = <#ty> :: recipe(tree);
-                                tree.create_struct_member(nid_struct, stringify!(#name), nid_ty);
-                            }
-                        });
+                        let members =
+                            struct_members(fields, quote!(nid_struct), &variant_attrs, false)?;
                         quote! {
                             let nid_struct = tree.create_struct(Some(nid_variant), "");
-                            #( #quotes_fields )*
+                            #members
                         }
                     }
                     Fields::Unnamed(ref fields) => {
                         // This enumeration has tuple data
                         // We create a `RecTuple` node as a child of a `RecEnumItem` node.
-                        let quotes_fields = fields.unnamed.iter().map(|f| {
+                        // Variants with a single field are newtype variants, which bincode
+                        // encodes like a tuple with one member.
+                        if fields.unnamed.len() == 1 {
+                            reject_newtype_skip(&fields.unnamed[0])?;
+                        }
+                        let mut quotes_fields = Vec::new();
+                        for f in &fields.unnamed {
+                            if Attrs::parse(&f.attrs)?.skip {
+                                continue;
+                            }
                             let ty = &f.ty;
-                            quote! {
+                            quotes_fields.push(quote! {
                                 let nid_ty = <#ty> :: recipe(tree);
                                 tree.create_tuple_member(nid_tuple, nid_ty);
-                            }
-                        });
+                            });
+                        }
                         quote! {
                             let nid_tuple = tree.create_tuple(Some(nid_variant));
                             #( #quotes_fields )*
                         }
                     }
-                    Fields::Unit => quote!{}
+                    Fields::Unit => quote! {},
                 };
-                quote! {
-                    let nid_variant = tree.create_enum_member(nid, stringify!(#name), next_enum_value.into());
+                quotes_variant.push(quote! {
+                    let nid_variant = tree.create_enum_member(nid, #name, #value);
                     #quote_fields
-                }
-            });
+                });
+            }
             quote! {
+                let nid_storage_ty = #storage;
+                let nid = tree.create_enum(None, "E", nid_storage_ty);
+                tree.register_type::<Self>(nid);
                 #( #quotes_variant )*
+                nid
             }
         }
         Data::Union(_) => unimplemented!(),
+    })
+}
+
+/// Return an error if the field of a newtype structure or variant is skipped, as the newtype would
+/// then have nothing to deserialize.
+///
+/// # Arguments
+///
+/// * `field` - Field of the newtype
+fn reject_newtype_skip(field: &Field) -> syn::Result<()> {
+    if Attrs::parse(&field.attrs)?.skip {
+        return Err(syn::Error::new(
+            field.span(),
+            "skip is not supported on the field of a newtype",
+        ));
+    }
+    Ok(())
+}
+
+/// Return the integer type given by the `#[repr]` attribute of an enumeration, if any. Pointer
+/// sized integers are serialized on 64 bits by bincode, so they are replaced by 64-bit integers.
+///
+/// # Arguments
+///
+/// * `attrs` - Attributes of the enumeration
+fn enum_repr(attrs: &[Attribute]) -> syn::Result<Option<TokenStream>> {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    let ty = match path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                        Some("usize") => quote!(u64),
+                        Some("isize") => quote!(i64),
+                        Some(
+                            "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64"
+                            | "i128",
+                        ) => quote!(#path),
+                        _ => continue,
+                    };
+                    return Ok(Some(ty));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Return the value of an enumeration discriminant, which must be an integer literal.
+///
+/// # Arguments
+///
+/// * `expr` - Discriminant expression
+fn discriminant_value(expr: &Expr) -> syn::Result<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-discriminant_value(expr)?),
+        Expr::Paren(paren) => discriminant_value(&paren.expr),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            "discriminant must be an integer literal",
+        )),
+    }
+}
+
+/// Return the statements creating the members of a structure, or of a structure variant of an
+/// enumeration.
+///
+/// # Arguments
+///
+/// * `fields` - Fields of the structure
+/// * `nid` - Variable holding the Id of the structure node
+/// * `container` - Options of the structure or variant, for `rename_all`
+/// * `container_default` - True if a `__default` variable holds the default value of the
+///   structure.
+fn struct_members(
+    fields: &FieldsNamed,
+    nid: TokenStream,
+    container: &Attrs,
+    container_default: bool,
+) -> syn::Result<TokenStream> {
+    let mut quotes = Vec::new();
+    for f in &fields.named {
+        let attrs = Attrs::parse(&f.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = f.ident.as_ref().unwrap();
+        let name = match (attrs.rename, container.rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.apply_to_field(&ident.unraw().to_string()),
+            (None, None) => ident.unraw().to_string(),
+        };
+        let ty = &f.ty;
+        let default = match attrs.default {
+            Some(DefaultValue::Trait) => Some(quote!(<#ty as ::core::default::Default>::default())),
+            Some(DefaultValue::Path(path)) => Some(quote!(#path())),
+            None if container_default => Some(quote!(__default.#ident)),
+            None => None,
+        };
+        quotes.push(match default {
+            Some(default) => quote_spanned! {
+                f.span() =>
+                    let nid_ty = <#ty> :: recipe(tree);
+                    tree.create_struct_member_with_default(#nid, #name, nid_ty, &#default);
+            },
+            None => quote_spanned! {
+                f.span() =>
+                    // To comply with borrow checker, those two lines cannot be merged.
+                    let nid_ty = <#ty> :: recipe(tree);
+                    tree.create_struct_member(#nid, #name, nid_ty);
+            },
+        });
     }
+    Ok(quote! {
+        #( #quotes )*
+    })
 }
@@ -0,0 +1,268 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::SourceLocation;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Secondary location of a diagnostic, with a message explaining its relation to the diagnostic
+#[derive(Debug, Clone)]
+pub struct Label {
+    location: SourceLocation,
+    message: String,
+}
+
+impl Label {
+    /// Location in the source code
+    pub fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    /// Message attached to the location
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Problem reported during the compilation of data
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: &'static str,
+    message: String,
+    location: Option<SourceLocation>,
+    related: Vec<Label>,
+}
+
+impl Diagnostic {
+    /// Create an error diagnostic.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Stable error code, such as `E0001`
+    /// * `message` - Description of the error
+    /// * `location` - Primary location of the error, None if the faulty node does not come from
+    ///   parsed source code (recipes built with the [`crate::Recipe`] trait for instance).
+    pub(crate) fn error(
+        code: &'static str,
+        message: String,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message,
+            location,
+            related: Vec::new(),
+        }
+    }
+
+    /// Add a related location to the diagnostic. Nothing is added if the location is None.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - Related location
+    /// * `message` - Relation of the location to the diagnostic
+    pub(crate) fn with_related(mut self, location: Option<SourceLocation>, message: &str) -> Self {
+        if let Some(location) = location {
+            self.related.push(Label {
+                location,
+                message: message.to_string(),
+            });
+        }
+        self
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Stable code identifying the kind of diagnostic, such as `E0001`
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Primary location of the diagnostic, if known
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.location.as_ref()
+    }
+
+    /// Other locations involved in the diagnostic
+    pub fn related(&self) -> &[Label] {
+        &self.related
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n --> {}", location)?;
+        }
+        for label in &self.related {
+            write!(f, "\n --> {}: {}", label.location, label.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders diagnostics as reports similar to the ones of rustc, with underlined source code
+/// excerpts for the primary and related locations.
+///
+/// Plain text is produced by default. ANSI colours can be enabled with [`Renderer::color`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer {
+    color: bool,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable ANSI colours in rendered reports
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - True to enable colours
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Render a diagnostic as a multiline report
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostic` - Diagnostic to be rendered
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let severity_style = match diagnostic.severity {
+            Severity::Error => BOLD_RED,
+            Severity::Warning => BOLD_YELLOW,
+        };
+        let mut out = format!(
+            "{}{}\n",
+            self.paint(
+                severity_style,
+                &format!("{}[{}]", diagnostic.severity, diagnostic.code)
+            ),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        );
+
+        // Labels as (location, message, primary). Labels in the same source are shown in the same
+        // excerpt, the excerpt of the primary location being the first one.
+        let mut groups: Vec<Vec<(&SourceLocation, &str, bool)>> = Vec::new();
+        let labels = diagnostic
+            .location
+            .iter()
+            .map(|location| (location, "", true))
+            .chain(
+                diagnostic
+                    .related
+                    .iter()
+                    .map(|label| (&label.location, label.message.as_str(), false)),
+            );
+        for label in labels {
+            match groups
+                .iter_mut()
+                .find(|group| Rc::ptr_eq(&group[0].0.source, &label.0.source))
+            {
+                Some(group) => group.push(label),
+                None => groups.push(vec![label]),
+            }
+        }
+
+        let width = groups
+            .iter()
+            .flatten()
+            .map(|label| label.0.line().to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = self.paint(BOLD_BLUE, &format!("{} |", " ".repeat(width)));
+        for (i, group) in groups.iter_mut().enumerate() {
+            let arrow = if i == 0 { "-->" } else { ":::" };
+            out += &format!(
+                "{}{} {}\n{}\n",
+                " ".repeat(width),
+                self.paint(BOLD_BLUE, arrow),
+                group[0].0,
+                gutter
+            );
+            group.sort_by_key(|label| label.0.start);
+            let mut previous_line = None;
+            for &(location, message, primary) in group.iter() {
+                let line = location.line();
+                let code = location.source.code.lines().nth(line - 1).unwrap_or("");
+                if previous_line != Some(line) {
+                    if previous_line.is_some_and(|previous| line > previous + 1) {
+                        out += &self.paint(BOLD_BLUE, "...");
+                        out.push('\n');
+                    }
+                    out += &format!(
+                        "{} {}\n",
+                        self.paint(BOLD_BLUE, &format!("{:>width$} |", line, width = width)),
+                        code
+                    );
+                }
+                previous_line = Some(line);
+
+                // Underline the location up to the end of its first line. Tabs are kept in the
+                // padding so the underline stays aligned.
+                let padding: String = code
+                    .chars()
+                    .take(location.column() - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let length = location.source.code[location.start..location.end]
+                    .chars()
+                    .take_while(|&c| c != '\n')
+                    .count()
+                    .max(1);
+                let (marker, style) = if primary {
+                    ('^', severity_style)
+                } else {
+                    ('-', BOLD_BLUE)
+                };
+                let mut underline = marker.to_string().repeat(length);
+                if !message.is_empty() {
+                    underline = format!("{} {}", underline, message);
+                }
+                out += &format!("{} {}{}\n", gutter, padding, self.paint(style, &underline));
+            }
+        }
+        out
+    }
+
+    /// Apply an ANSI style to a text if colours are enabled
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
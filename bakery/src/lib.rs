@@ -28,16 +28,23 @@
 //! ```
 
 use num_bigint::{BigInt, Sign};
+use pest::error::{ErrorVariant, LineColLocation};
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
 use pest::Span;
 use pest_derive::Parser;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Duration;
 
 mod tree;
 use tree::Tree;
@@ -45,6 +52,9 @@ use tree::Tree;
 mod recipe;
 pub use recipe::Recipe;
 
+mod diagnostic;
+pub use diagnostic::{Diagnostic, Label, Renderer, Severity};
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 struct MyParser;
@@ -52,9 +62,27 @@ struct MyParser;
 type WriteResult = Result<(), std::io::Error>;
 
 enum CompilationError {
+    AliasCycle(u32),
+    ArraySizeMismatch {
+        node_array: u32,
+        node_data: u32,
+        expected: usize,
+    },
     DataNotStruct(u32),
+    DuplicateMapKey {
+        first: u32,
+        second: u32,
+    },
+    DuplicateSetItem {
+        first: u32,
+        second: u32,
+    },
     EnumTypeIsNotInt(u32),
     EnumValueOutOfBounds(u32),
+    EnumDuplicateValue {
+        first: u32,
+        second: u32,
+    },
     EnumUndefinedName {
         node_enum: u32,
         node_name: u32,
@@ -64,20 +92,44 @@ enum CompilationError {
     },
     ExpectedDatFloat(u32),
     ExpectedDatInt(u32),
+    ExpectedDatList(u32),
+    ExpectedDatMap(u32),
+    ExpectedDatOption(u32),
+    ExpectedDatSet(u32),
+    ExpectedDatString(u32),
+    ExpectedDatChar(u32),
     ExpectedDatStruct(u32),
+    ExpectedDatTuple(u32),
     ExpectedDatIdentifier(u32),
+    ImportCycle {
+        path: String,
+        node: u32,
+    },
+    ImportNotFound {
+        path: String,
+        node: u32,
+    },
     GenericArgCountMismatch {
         nid: u32,
         expected: usize,
         current: usize,
     },
-    IOError(std::io::Error),
-    RedefinedValue(u32),
+    InvalidEscapeSequence(u32),
+    InvalidFloat(u32),
+    RedefinedValue {
+        first: u32,
+        second: u32,
+    },
     TupleSizeMismatch {
         node_tuple: u32,
         node_data: u32,
     },
     UndefinedValue(u32),
+    UnknownMember {
+        node: u32,
+        node_struct: u32,
+        suggestions: Vec<String>,
+    },
     UnresolvedType {
         path: String,
         node: u32,
@@ -85,21 +137,89 @@ enum CompilationError {
     ValueOutOfBounds(u32),
 }
 
+/// Source code of a recipe or data
 #[derive(Debug)]
+pub struct Source {
+    /// Path of the file the code has been read from, None if the code does not come from a file.
+    path: Option<PathBuf>,
+    code: String,
+}
+
+impl Source {
+    /// Create a source.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file the code has been read from, if any
+    /// * `code` - Source code
+    fn new(path: Option<&Path>, code: &str) -> Rc<Self> {
+        Rc::new(Source {
+            path: path.map(Path::to_path_buf),
+            code: code.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SourceLocation {
-    source: Rc<String>,
+    source: Rc<Source>,
     start: usize,
     end: usize,
 }
 
 impl SourceLocation {
-    fn new_from_span(source: Rc<String>, span: Span<'_>) -> Self {
+    fn new_from_span(source: Rc<Source>, span: Span<'_>) -> Self {
         SourceLocation {
-            source: source,
+            source,
             start: span.start(),
             end: span.end(),
         }
     }
+
+    /// Source code the location refers to
+    pub fn source(&self) -> &str {
+        &self.source.code
+    }
+
+    /// Path of the file the location refers to, None if the source code does not come from a file
+    pub fn path(&self) -> Option<&Path> {
+        self.source.path.as_deref()
+    }
+
+    /// Byte offset of the start of the location in the source code
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Byte offset of the end of the location in the source code
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Line of the start of the location, starting at 1
+    pub fn line(&self) -> usize {
+        self.source.code[..self.start].matches('\n').count() + 1
+    }
+
+    /// Column of the start of the location, in characters, starting at 1
+    pub fn column(&self) -> usize {
+        self.source.code[..self.start]
+            .chars()
+            .rev()
+            .take_while(|&c| c != '\n')
+            .count()
+            + 1
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path() {
+            Some(path) => write!(f, "{}", path.display())?,
+            None => write!(f, "<string>")?,
+        }
+        write!(f, ":{}:{}", self.line(), self.column())
+    }
 }
 
 /// Identifies a type in the node tree
@@ -141,10 +261,50 @@ pub enum NodeContent {
     RecFloat {
         size: u32,
     },
+    /// UTF-8 string type
+    ///
+    /// Nodes of this type are created by the compiler, to populate the standard String type.
+    RecString,
+    /// Unicode scalar value type
+    ///
+    /// Nodes of this type are created by the compiler, to populate the standard char type.
+    RecChar,
     /// Generic list type
     RecList,
     /// Generic map type
-    RecMap,
+    ///
+    /// Written as a `u64` length followed by the entries. When `sorted` is true, or when the
+    /// `sort_maps` compilation option is set, entries are sorted by key value instead of being
+    /// written in the order of the data.
+    RecMap {
+        sorted: bool,
+    },
+    /// Fixed-size array type
+    ///
+    /// The type of the items is the first and unique child. Arrays are written without any length
+    /// prefix.
+    RecArray {
+        len: usize,
+    },
+    /// Generic set type
+    ///
+    /// Written as a `u64` length followed by the items, sorted by value so the output does not
+    /// depend on the order of the data. Duplicate items are rejected.
+    RecSet,
+    /// Generic optional value type
+    ///
+    /// Written as a `u8` tag, 0 for `None` and 1 for `Some`, followed by the value when defined.
+    RecOption,
+    /// Generic boxed value type, also used for reference counted pointers and derived newtypes
+    ///
+    /// Written as the boxed value itself. Boxes only exist to mirror Rust types, they are not
+    /// required for recursive types.
+    RecBox,
+    /// Type alias
+    ///
+    /// Name of the node is the alias name. Children are the `RecGeneric` nodes of the alias
+    /// generic parameters, followed by the aliased type node.
+    RecAlias,
     /// Recipe enumeration definition
     ///
     /// `RecEnum` nodes have one `RecEnumItem` child node for each possible enumeration value.
@@ -154,19 +314,34 @@ pub enum NodeContent {
     /// An enumeration possible value
     ///
     /// RecEnumItem can have one child, which can be a RecTuple or RecStruct
+    ///
+    /// If `explicit` is false, `value` is calculated during compilation as the value of the
+    /// previous item plus one, or 0 for the first item.
     RecEnumItem {
         value: BigInt,
+        explicit: bool,
     },
     RecStruct,
+    /// Module, a namespace which can only contain type declarations
+    ///
+    /// Imported recipe files are loaded as modules.
+    RecMod,
+    /// Import declaration of a recipe file
+    ///
+    /// Import nodes are replaced by `RecMod` nodes when the imported file is loaded, before type
+    /// resolution.
+    RecImport {
+        path: String,
+    },
     /// Members of structures
     /// Name of the node is the member name in the structure
-    /// Type of the structure member is first and unique child
+    /// Type of the structure member is the first child
+    /// An optional second child is the data node of the default value of the member.
     RecStructMember,
     /// Members of tuples
     /// Name of the node is None
-    RecTupleMember {
-        tid: RecTypeId,
-    },
+    /// Type of the tuple member is the only child
+    RecTupleMember,
     /// Node designating another existing type in the tree
     /// Firstly stores the path string to the type, and then is resolved as the pointed type node
     /// Id.
@@ -184,6 +359,7 @@ pub enum NodeContent {
     DatMap,
     DatTuple,
     DatList,
+    DatSet,
     /// This node corresponds to a map or structure assignment (we can consider structures as a
     /// subset of maps from a grammar perspective).
     /// Such node have two childen, one for the name or key, and a second for the value.
@@ -196,20 +372,26 @@ pub enum NodeContent {
     DatFloat {
         repr: String,
     },
+    /// String literal, either quoted or raw.
+    /// `repr` is the literal as written in the source, escape sequences are decoded when written.
+    DatString {
+        repr: String,
+    },
+    /// Character literal, with the same escape sequences as string literals.
+    /// `repr` is the literal as written in the source, including the quotes.
+    DatChar {
+        repr: String,
+    },
     /// Enumeration identifier (works for boolean too)
     /// Enumeration value name stored in node name
     /// Also used for structure assignments.
     DatEnum,
-}
-
-impl NodeContent {
-    /// Return true if node can have generic type arguments
-    fn may_be_generic(&self) -> bool {
-        match self {
-            NodeContent::RecStruct | NodeContent::RecList | NodeContent::RecMap => true,
-            _ => false,
-        }
-    }
+    /// Value already encoded with bincode, written as is.
+    /// Used for default values of structure members created by the derived `Recipe` trait, which
+    /// come from Rust values rather than from data files.
+    DatBinary {
+        bytes: Vec<u8>,
+    },
 }
 
 /// Node for recipe tree
@@ -234,7 +416,7 @@ impl Node {
         Node {
             name: Some(name.to_string()),
             source: None,
-            content: content,
+            content,
         }
     }
 
@@ -247,7 +429,7 @@ impl Node {
         Node {
             name: None,
             source: None,
-            content: content,
+            content,
         }
     }
 
@@ -259,9 +441,77 @@ impl Node {
     }
 }
 
-pub type NodeTree = Tree<Node>;
+/// Recipe node tree
+///
+/// Types built with the [`Recipe`] trait can be registered by their Rust type, so that they are
+/// built only once. Recursive types then refer to the type under construction instead of expanding
+/// it again.
+#[derive(Debug)]
+pub struct NodeTree {
+    tree: Tree<Node>,
+    /// Registered type nodes, by Rust type. Type names are not unique, so they can't be used as
+    /// keys.
+    types: HashMap<TypeId, u32>,
+}
+
+impl Default for NodeTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for NodeTree {
+    type Target = Tree<Node>;
+
+    fn deref(&self) -> &Tree<Node> {
+        &self.tree
+    }
+}
+
+impl DerefMut for NodeTree {
+    fn deref_mut(&mut self) -> &mut Tree<Node> {
+        &mut self.tree
+    }
+}
 
 impl NodeTree {
+    pub fn new() -> NodeTree {
+        NodeTree {
+            tree: Tree::new(),
+            types: HashMap::new(),
+        }
+    }
+
+    /// Return the node of a type previously registered with [`NodeTree::register_type`], or None
+    /// if the type has not been registered yet.
+    pub fn registered_type<T: ?Sized + 'static>(&self) -> Option<u32> {
+        self.types.get(&TypeId::of::<T>()).copied()
+    }
+
+    /// Register the node of a Rust type. This must be done before building the nodes of the type
+    /// members, so that recursive types can refer to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid` - Type node Id
+    pub fn register_type<T: ?Sized + 'static>(&mut self, nid: u32) {
+        self.types.insert(TypeId::of::<T>(), nid);
+    }
+
+    /// Create a node referring to an existing type node, and return created node Id.
+    ///
+    /// Type nodes can only have one parent, so this is required to use a type node more than
+    /// once.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid_type` - Referred type node Id
+    pub fn create_type_ref(&mut self, nid_type: u32) -> u32 {
+        self.create(Node::new_anonymous(NodeContent::RecTypeInst {
+            tid: RecTypeId::Id(nid_type),
+        }))
+    }
+
     /// Create a generic type and return created node Id.
     ///
     /// # Arguments
@@ -282,7 +532,7 @@ impl NodeTree {
             Node {
                 name: Some(name.to_string()),
                 source: None,
-                content: content,
+                content,
             },
         );
         for i in 0..n {
@@ -340,6 +590,35 @@ impl NodeTree {
         nid
     }
 
+    /// Create a recipe structure member node with a default value and return node Id.
+    ///
+    /// The default value is used when the member is not defined in the data. It is encoded with
+    /// bincode when the recipe is built, so it must serialize the same way the member type
+    /// deserializes.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - Parent node
+    /// * `name` - Member name
+    /// * `nid_type` - Node for the type of the structure
+    /// * `default` - Default value of the member
+    pub fn create_struct_member_with_default<T: Serialize>(
+        &mut self,
+        parent: u32,
+        name: &str,
+        nid_type: u32,
+        default: &T,
+    ) -> u32 {
+        let nid = self.create_struct_member(parent, name, nid_type);
+        self.create_with_parent(
+            Some(nid),
+            Node::new_anonymous(NodeContent::DatBinary {
+                bytes: bincode::serialize(default).unwrap(),
+            }),
+        );
+        nid
+    }
+
     /// Create a recipe enumeration node and return node Id.
     ///
     /// # Arguments
@@ -365,13 +644,18 @@ impl NodeTree {
     ///
     /// * `parent` - Parent node
     /// * `name` - Enumeration member name
-    pub fn create_enum_member(&mut self, parent: u32, name: &str, value: BigInt) -> u32 {
+    /// * `value` - Enumeration member value, or None to use the value of the previous member plus
+    ///   one.
+    pub fn create_enum_member(&mut self, parent: u32, name: &str, value: Option<BigInt>) -> u32 {
         self.create_with_parent(
             Some(parent),
             Node {
                 name: Some(name.to_string()),
                 source: None,
-                content: NodeContent::RecEnumItem { value: value },
+                content: NodeContent::RecEnumItem {
+                    explicit: value.is_some(),
+                    value: value.unwrap_or_default(),
+                },
             },
         )
     }
@@ -392,12 +676,33 @@ impl NodeTree {
     /// * `parent` - Parent node
     /// * `ty` - Type node
     pub fn create_tuple_member(&mut self, parent: u32, ty: u32) -> u32 {
-        self.create_with_parent(
+        let nid = self.create_with_parent(
             Some(parent),
-            Node::new_anonymous(NodeContent::RecTupleMember {
-                tid: RecTypeId::Id(ty),
-            }),
-        )
+            Node::new_anonymous(NodeContent::RecTupleMember),
+        );
+        self.child(nid, ty);
+        nid
+    }
+
+    /// Create a newtype node and return node Id. Newtypes are written as their content, whose type
+    /// is set afterwards with [`NodeTree::set_newtype_content`], so that the newtype can be
+    /// registered before its content is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - Parent node
+    pub fn create_newtype(&mut self, parent: Option<u32>) -> u32 {
+        self.create_with_parent(parent, Node::new_anonymous(NodeContent::RecBox))
+    }
+
+    /// Set the content type of a newtype node.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid` - Newtype node
+    /// * `ty` - Content type node
+    pub fn set_newtype_content(&mut self, nid: u32, ty: u32) {
+        self.child(nid, ty);
     }
 
     /// Parse a type recipe from a string, return recipe node Id.
@@ -406,18 +711,21 @@ impl NodeTree {
     ///
     /// * `rec` - Recipe string
     pub fn parse_recipe_string(&mut self, rec: &str) -> Result<u32, LoadError> {
-        let rec = rec.trim_start().trim_end();
-        let mut pairs = MyParser::parse(Rule::rec_type_anonymous, rec).unwrap();
+        self.parse_recipe_source(Source::new(None, rec))
+    }
+
+    /// Parse a type recipe, return recipe node Id.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Recipe source
+    fn parse_recipe_source(&mut self, source: Rc<Source>) -> Result<u32, LoadError> {
+        // Entry rules end with EOI, so parsing fails if there's garbage after the recipe.
+        let mut pairs = MyParser::parse(Rule::rec_type_input, &source.code)
+            .map_err(|e| LoadError::RecipeParseError(ParseError::new(&source, e)))?;
         let pair = pairs.next().unwrap();
-        let span = pair.as_span();
-        assert!(pairs.next().is_none());
-        // Check the the whole string has been parsed.
-        // pest will match as much as it can, so if there's garbage at the end, it will be ignored.
-        // We don't want to ignore this garbage.
-        if span.end() != rec.len() {
-            return Err(LoadError::RecipeParseError);
-        }
-        Ok(self.parse_rec_type(Rc::new(rec.to_string()), pair))
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::EOI);
+        Ok(self.parse_rec_type(source.clone(), pair))
     }
 
     /// Build recipe from a string, return recipe node Id.
@@ -427,27 +735,36 @@ impl NodeTree {
     /// * `rec` - Recipe string
     pub fn parse_struct_recipe_string(&mut self, rec: &str) -> Result<u32, LoadError> {
         // Parse recipe
-        let rec = rec.trim_start().trim_end();
-        let mut pairs = MyParser::parse(Rule::file_rec, rec).unwrap();
+        let source = Source::new(None, rec);
+        let mut pairs = MyParser::parse(Rule::file_rec, &source.code)
+            .map_err(|e| LoadError::RecipeParseError(ParseError::new(&source, e)))?;
         let pair = pairs.next().unwrap();
-        let span = pair.as_span();
-        assert!(pairs.next().is_none());
-        // Check the the whole string has been parsed.
-        // pest will match as much as it can, so if there's garbage at the end, it will be ignored.
-        // We don't want to ignore this garbage.
-        if span.end() != rec.len() {
-            return Err(LoadError::RecipeParseError);
-        }
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::EOI);
 
         let nid = self.create(Node {
             name: None,
             source: None,
             content: NodeContent::RecStruct,
         });
-        self.parse_rec_struct_declarations(Rc::new(rec.to_string()), pair.into_inner(), nid);
+        self.parse_rec_struct_declarations(source.clone(), pair.into_inner(), nid);
         Ok(nid)
     }
 
+    /// Parse the declarations of an imported recipe file as children of a module node.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Imported file source
+    /// * `mod_nid` - Module node receiving the declarations
+    fn parse_mod_source(&mut self, source: Rc<Source>, mod_nid: u32) -> Result<(), LoadError> {
+        let mut pairs = MyParser::parse(Rule::file_mod, &source.code)
+            .map_err(|e| LoadError::RecipeParseError(ParseError::new(&source, e)))?;
+        let pair = pairs.next().unwrap();
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::EOI);
+        self.parse_rec_struct_declarations(source.clone(), pair.into_inner(), mod_nid);
+        Ok(())
+    }
+
     /// Parse and create a data value node from a string, returning created node Id or compilation
     /// error.
     ///
@@ -455,17 +772,20 @@ impl NodeTree {
     ///
     /// * `dat` - Data string
     pub fn parse_dat_value_string(&mut self, dat: &str) -> Result<u32, LoadError> {
-        let dat = dat.trim_start().trim_end();
-        let mut pairs = MyParser::parse(Rule::dat_value, dat).unwrap();
+        self.parse_dat_value_source(Source::new(None, dat))
+    }
+
+    /// Parse and create a data value node, returning created node Id or compilation error.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Data source
+    fn parse_dat_value_source(&mut self, source: Rc<Source>) -> Result<u32, LoadError> {
+        let mut pairs = MyParser::parse(Rule::dat_value_input, &source.code)
+            .map_err(|e| LoadError::DataParseError(ParseError::new(&source, e)))?;
         let pair = pairs.next().unwrap();
-        let span = pair.as_span();
-        assert!(pairs.next().is_none());
-        // Check parsing completeness
-        if span.end() != dat.len() {
-            Err(LoadError::DataParseError)
-        } else {
-            Ok(self.parse_dat_value(Rc::new(dat.to_string()), pair))
-        }
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::EOI);
+        Ok(self.parse_dat_value(source.clone(), pair))
     }
 
     /// Parse and create a DatMap node from a string, returning created node Id or compilation
@@ -475,17 +795,20 @@ impl NodeTree {
     ///
     /// * `dat` - Data string, struct format without the braces.
     pub fn parse_dat_map_string(&mut self, dat: &str) -> Result<u32, LoadError> {
-        let dat = dat.trim_start().trim_end();
-        let mut pairs = MyParser::parse(Rule::file_dat, dat).unwrap();
+        self.parse_dat_map_source(Source::new(None, dat))
+    }
+
+    /// Parse and create a DatMap node, returning created node Id or compilation error.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Data source, struct format without the braces.
+    fn parse_dat_map_source(&mut self, source: Rc<Source>) -> Result<u32, LoadError> {
+        let mut pairs = MyParser::parse(Rule::file_dat, &source.code)
+            .map_err(|e| LoadError::DataParseError(ParseError::new(&source, e)))?;
         let pair = pairs.next().unwrap();
-        let span = pair.as_span();
-        assert!(pairs.next().is_none());
-        // Check parsing completeness
-        if span.end() != dat.len() {
-            Err(LoadError::DataParseError)
-        } else {
-            Ok(self.parse_dat_map(Rc::new(dat.to_string()), pair))
-        }
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::EOI);
+        Ok(self.parse_dat_map(source.clone(), pair))
     }
 
     /// Parse and create a type recipe node, returns created node Id.
@@ -493,14 +816,13 @@ impl NodeTree {
     /// # Arguments
     ///
     /// * `pair` - pest parser pair to be read, must be a pair describing a type.
-    fn parse_rec_type(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_rec_type(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         match pair.as_rule() {
             Rule::rec_type_inst => self.parse_rec_type_inst(source, pair),
-            Rule::rec_struct => self.parse_rec_struct(source, pair),
             Rule::rec_struct_anonymous => self.parse_rec_struct(source, pair),
-            Rule::rec_enum => self.parse_rec_enum(source, pair),
             Rule::rec_enum_anonymous => self.parse_rec_enum(source, pair),
             Rule::rec_tuple => self.parse_rec_tuple(source, pair),
+            Rule::rec_array => self.parse_rec_array(source, pair),
             _ => {
                 panic!();
             }
@@ -513,7 +835,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read, must be a pair describing a type.
-    fn parse_rec_type_inst(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_rec_type_inst(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
         let mut inner = pair.into_inner();
         let nid = self.create(Node {
@@ -526,7 +848,7 @@ impl NodeTree {
         // The type instantiation may have generic type arguments
         if let Some(pair) = inner.next() {
             for pair in pair.into_inner() {
-                let generic_argument_nid = self.parse_rec_type_inst(source.clone(), pair);
+                let generic_argument_nid = self.parse_rec_type(source.clone(), pair);
                 self.child(nid, generic_argument_nid);
             }
         }
@@ -540,8 +862,8 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_rec_struct(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
-        let span = pair.as_span().clone();
+    fn parse_rec_struct(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
+        let span = pair.as_span();
         let mut inner = pair.into_inner();
         // Structure can be anonymous or not
         let mut pair = inner.next().unwrap();
@@ -551,7 +873,7 @@ impl NodeTree {
             pair = inner.next().unwrap();
         };
         let id = self.create(Node {
-            name: name,
+            name,
             source: Some(SourceLocation::new_from_span(source.clone(), span)),
             content: NodeContent::RecStruct,
         });
@@ -564,19 +886,19 @@ impl NodeTree {
         id
     }
 
-    /// Parse a generic types declaration of a structure node.
-    /// For each generic type, a `RecGeneric` child node is added to the structure node.
+    /// Parse a generic types declaration of a structure, enumeration or type alias node.
+    /// For each generic type, a `RecGeneric` child node is added to the declaring node.
     ///
     /// # Arguments
     ///
     /// * `source` - Currently parsed source code
-    /// * `struct_nid` - Structure node Id
+    /// * `nid` - Structure, enumeration or type alias node Id
     /// * `pair` - pair of rule `Rule::rec_generic_decl` to be parsed
-    fn parse_rec_generic_decl(&mut self, source: Rc<String>, struct_nid: u32, pair: Pair<Rule>) {
+    fn parse_rec_generic_decl(&mut self, source: Rc<Source>, nid: u32, pair: Pair<Rule>) {
         let mut arg_index = 0;
         for pair in pair.into_inner() {
             self.create_with_parent(
-                Some(struct_nid),
+                Some(nid),
                 Node {
                     name: Some(pair.as_str().to_string()),
                     source: Some(SourceLocation::new_from_span(
@@ -596,7 +918,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_rec_tuple(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_rec_tuple(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         // Create the tuple type node
         let tuple_nid = self.create(Node {
             name: None,
@@ -607,7 +929,7 @@ impl NodeTree {
             content: NodeContent::RecTuple,
         });
         for pair in pair.into_inner() {
-            self.create_with_parent(
+            let member_nid = self.create_with_parent(
                 Some(tuple_nid),
                 Node {
                     name: None,
@@ -615,15 +937,41 @@ impl NodeTree {
                         source.clone(),
                         pair.as_span(),
                     )),
-                    content: NodeContent::RecTupleMember {
-                        tid: RecTypeId::Path(pair.as_str().to_string()),
-                    },
+                    content: NodeContent::RecTupleMember,
                 },
             );
+            let type_nid = self.parse_rec_type(source.clone(), pair);
+            self.child(member_nid, type_nid);
         }
         tuple_nid
     }
 
+    /// Parse and create an array recipe node, returns created node Id.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Currently parsed source code
+    /// * `pair` - pest parser pair to be read
+    fn parse_rec_array(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
+        let span = pair.as_span();
+        let mut inner = pair.into_inner();
+        let item_type_nid = self.parse_rec_type(source.clone(), inner.next().unwrap());
+        let len_pair = inner.next().unwrap();
+        assert_eq!(inner.next(), None);
+        let nid = self.create(Node {
+            name: None,
+            source: Some(SourceLocation::new_from_span(source, span)),
+            content: NodeContent::RecArray {
+                // The grammar only allows digits, so parsing can only fail if the value is too
+                // large. Such a size can never match the data, which is reported as an
+                // `ArraySizeMismatch` error during compilation.
+                len: len_pair.as_str().parse().unwrap_or(usize::MAX),
+            },
+        });
+        self.child(nid, item_type_nid);
+        nid
+    }
+
     /// Parse the members of a structure recipe node
     ///
     /// This method is separated from parse_rec_struct because it is used to parse a recipe file as
@@ -636,7 +984,7 @@ impl NodeTree {
     /// * `parent` - Recipe members parent node
     fn parse_rec_struct_declarations(
         &mut self,
-        source: Rc<String>,
+        source: Rc<Source>,
         inner: Pairs<Rule>,
         parent: u32,
     ) {
@@ -652,7 +1000,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_rec_struct_declaration(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_rec_struct_declaration(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
         match pair.as_rule() {
             Rule::member => {
@@ -666,12 +1014,57 @@ impl NodeTree {
                 // Child is most of the time a RecTypeId, but it can also be directly a RecTuple,
                 // RecStruct, etc.
                 let type_pair = inner.next().unwrap();
-                let type_nid = self.parse_rec_type(source, type_pair);
+                let type_nid = self.parse_rec_type(source.clone(), type_pair);
                 self.child(struct_nid, type_nid);
+                // Default value
+                if let Some(default_pair) = inner.next() {
+                    let default_nid = self.parse_dat_value(source, default_pair);
+                    self.child(struct_nid, default_nid);
+                }
                 struct_nid
             }
             Rule::rec_struct => self.parse_rec_struct(source, pair),
             Rule::rec_enum => self.parse_rec_enum(source, pair),
+            Rule::rec_alias => {
+                let mut inner = pair.into_inner();
+                let alias_nid = self.create(Node {
+                    name: Some(inner.next().unwrap().as_str().to_string()),
+                    source: Some(SourceLocation::new_from_span(source.clone(), span)),
+                    content: NodeContent::RecAlias,
+                });
+                let mut pair = inner.next().unwrap();
+                if pair.as_rule() == Rule::rec_generic_decl {
+                    self.parse_rec_generic_decl(source.clone(), alias_nid, pair);
+                    pair = inner.next().unwrap();
+                }
+                let type_nid = self.parse_rec_type(source, pair);
+                self.child(alias_nid, type_nid);
+                alias_nid
+            }
+            Rule::rec_mod => {
+                let mut inner = pair.into_inner();
+                let mod_nid = self.create(Node {
+                    name: Some(inner.next().unwrap().as_str().to_string()),
+                    source: Some(SourceLocation::new_from_span(source.clone(), span)),
+                    content: NodeContent::RecMod,
+                });
+                self.parse_rec_struct_declarations(
+                    source,
+                    inner.next().unwrap().into_inner(),
+                    mod_nid,
+                );
+                mod_nid
+            }
+            Rule::rec_import => {
+                let path_pair = pair.into_inner().next().unwrap();
+                self.create(Node {
+                    name: None,
+                    source: Some(SourceLocation::new_from_span(source, span)),
+                    content: NodeContent::RecImport {
+                        path: path_pair.as_str().to_string(),
+                    },
+                })
+            }
             _ => {
                 panic!()
             }
@@ -684,43 +1077,53 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_rec_enum(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_rec_enum(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
-        let mut inner = pair.into_inner();
-        let mut pair = inner.next().unwrap();
-        let name = if pair.as_rule() == Rule::identifier {
-            let res = Some(pair.as_str().to_string());
-            pair = inner.next().unwrap();
-            res
-        } else {
-            None
+        let mut inner = pair.into_inner().peekable();
+        let name = match inner.peek() {
+            Some(pair) if pair.as_rule() == Rule::identifier => {
+                Some(inner.next().unwrap().as_str().to_string())
+            }
+            _ => None,
+        };
+        let generic_decl = inner.next_if(|pair| pair.as_rule() == Rule::rec_generic_decl);
+        let key_type = match inner.peek() {
+            Some(pair) if pair.as_rule() == Rule::rec_enum_storage => {
+                inner.next().unwrap().into_inner().as_str().to_string()
+            }
+            _ => "i32".to_string(),
         };
         let enum_nid = self.create(Node {
-            name: name,
+            name,
             source: Some(SourceLocation::new_from_span(source.clone(), span)),
             content: NodeContent::RecEnum {
-                key_type: RecTypeId::Path("i32".to_string()),
+                key_type: RecTypeId::Path(key_type),
             },
         });
-        // Walk all enumeration values
-        for pair in pair.into_inner() {
+        if let Some(pair) = generic_decl {
+            self.parse_rec_generic_decl(source.clone(), enum_nid, pair);
+        }
+        // Walk all enumeration values. Enumeration may be empty.
+        for pair in inner.next().into_iter().flat_map(|pair| pair.into_inner()) {
             let span = pair.as_span();
-            let mut inner = pair.into_inner();
+            let mut inner = pair.into_inner().peekable();
             let name = inner.next().unwrap().as_str().to_string();
             let enum_item_nid = self.create_with_parent(
                 Some(enum_nid),
                 Node {
                     name: Some(name),
-                    source: Some(SourceLocation::new_from_span(source.clone(), span.clone())),
+                    source: Some(SourceLocation::new_from_span(source.clone(), span)),
                     content: NodeContent::RecEnumItem {
                         value: BigInt::from(0),
+                        explicit: false,
                     },
                 },
             );
             // Build data type tuple if defined
-            if let Some(pair) = inner.next() {
+            if let Some(pair) = inner.next_if(|pair| pair.as_rule() != Rule::rec_enum_discriminant)
+            {
                 match pair.as_rule() {
-                    Rule::rec_enum_tuple => {
+                    Rule::rec_tuple => {
                         let tuple_nid = self.parse_rec_tuple(source.clone(), pair);
                         self.child(enum_item_nid, tuple_nid);
                     }
@@ -744,6 +1147,18 @@ impl NodeTree {
                     } // Case not allowed by grammar
                 }
             };
+            // Explicit value
+            if let Some(pair) = inner.next() {
+                let value = parse_int_literal(pair.into_inner().as_str());
+                if let NodeContent::RecEnumItem {
+                    value: item_value,
+                    explicit,
+                } = &mut self.get_mut(enum_item_nid).content
+                {
+                    *item_value = value;
+                    *explicit = true;
+                }
+            }
             assert!(inner.next().is_none());
         }
         assert!(inner.next().is_none());
@@ -756,7 +1171,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_dat_map(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_dat_map(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
         let node = self.create(Node {
             name: None,
@@ -778,7 +1193,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_dat_map_assignment(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_dat_map_assignment(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
         let mut inner = pair.into_inner();
         let nid_key = self.parse_dat_value(source.clone(), inner.next().unwrap());
@@ -794,20 +1209,20 @@ impl NodeTree {
         nid
     }
 
-    /// Parse and create a tuple data node, returns created node Id.
+    /// Parse and create a tuple, list or set data node, returns created node Id.
     ///
     /// # Arguments
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
     /// * `content` - Content for the created node
-    fn parse_dat_tuple_or_list(
+    fn parse_dat_sequence(
         &mut self,
-        source: Rc<String>,
+        source: Rc<Source>,
         pair: Pair<Rule>,
         content: NodeContent,
     ) -> u32 {
-        if let NodeContent::DatTuple | NodeContent::DatList = content {
+        if let NodeContent::DatTuple | NodeContent::DatList | NodeContent::DatSet = content {
         } else {
             panic!();
         }
@@ -817,7 +1232,7 @@ impl NodeTree {
                 source.clone(),
                 pair.as_span(),
             )),
-            content: content,
+            content,
         });
         for p in pair.into_inner() {
             let child = self.parse_dat_value(source.clone(), p);
@@ -832,7 +1247,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_dat_enum(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_dat_enum(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         let span = pair.as_span();
         let mut inner = pair.into_inner();
         let node = self.create(Node {
@@ -844,7 +1259,7 @@ impl NodeTree {
         if let Some(pair) = inner.next() {
             let child_nid = match pair.as_rule() {
                 Rule::dat_tuple => {
-                    self.parse_dat_tuple_or_list(source.clone(), pair, NodeContent::DatTuple)
+                    self.parse_dat_sequence(source.clone(), pair, NodeContent::DatTuple)
                 }
                 Rule::dat_map => self.parse_dat_map(source, pair),
                 _ => panic!(),
@@ -860,7 +1275,7 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_dat_primitive_value(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_dat_primitive_value(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         self.create(Node {
             name: None,
             source: Some(SourceLocation::new_from_span(source, pair.as_span())),
@@ -871,6 +1286,12 @@ impl NodeTree {
                 Rule::float => NodeContent::DatFloat {
                     repr: pair.as_str().to_string(),
                 },
+                Rule::string => NodeContent::DatString {
+                    repr: pair.as_str().to_string(),
+                },
+                Rule::character => NodeContent::DatChar {
+                    repr: pair.as_str().to_string(),
+                },
                 _ => panic!(),
             },
         })
@@ -882,13 +1303,16 @@ impl NodeTree {
     ///
     /// * `source` - Currently parsed source code
     /// * `pair` - pest parser pair to be read
-    fn parse_dat_value(&mut self, source: Rc<String>, pair: Pair<Rule>) -> u32 {
+    fn parse_dat_value(&mut self, source: Rc<Source>, pair: Pair<Rule>) -> u32 {
         match pair.as_rule() {
-            Rule::int | Rule::float => self.parse_dat_primitive_value(source, pair),
+            Rule::int | Rule::float | Rule::string | Rule::character => {
+                self.parse_dat_primitive_value(source, pair)
+            }
             Rule::dat_map => self.parse_dat_map(source, pair),
             Rule::dat_enum => self.parse_dat_enum(source, pair),
-            Rule::dat_tuple => self.parse_dat_tuple_or_list(source, pair, NodeContent::DatTuple),
-            Rule::dat_list => self.parse_dat_tuple_or_list(source, pair, NodeContent::DatList),
+            Rule::dat_tuple => self.parse_dat_sequence(source, pair, NodeContent::DatTuple),
+            Rule::dat_list => self.parse_dat_sequence(source, pair, NodeContent::DatList),
+            Rule::dat_set => self.parse_dat_sequence(source, pair, NodeContent::DatSet),
             _ => panic!(),
         }
     }
@@ -898,16 +1322,17 @@ impl NodeTree {
     fn number_of_generic_types(&self, nid: u32) -> usize {
         let node = self.get_item(nid);
         match node.value.content {
-            NodeContent::RecStruct | NodeContent::RecList | NodeContent::RecMap => node
+            NodeContent::RecStruct
+            | NodeContent::RecEnum { .. }
+            | NodeContent::RecList
+            | NodeContent::RecSet
+            | NodeContent::RecMap { .. }
+            | NodeContent::RecOption
+            | NodeContent::RecBox
+            | NodeContent::RecAlias => node
                 .children()
                 .iter()
-                .filter(|&&nid| {
-                    if let NodeContent::RecGeneric { .. } = self.get(nid).content {
-                        true
-                    } else {
-                        false
-                    }
-                })
+                .filter(|&&nid| matches!(self.get(nid).content, NodeContent::RecGeneric { .. }))
                 .count(),
             _ => 0,
         }
@@ -924,11 +1349,7 @@ impl NodeTree {
             Some(parent_id) => {
                 match self.get_item(parent_id).parent {
                     // If the parent is root, don't write it in the path for better display for humans.
-                    Some(_) => {
-                        self.node_path(parent_id)
-                            + &"::".to_string()
-                            + &node.value.name_or_anonymous()
-                    }
+                    Some(_) => self.node_path(parent_id) + "::" + &node.value.name_or_anonymous(),
                     None => node.value.name_or_anonymous(),
                 }
             }
@@ -994,8 +1415,24 @@ impl NodeTree {
                     signed: false,
                 },
             ),
+            (
+                "i128",
+                NodeContent::RecInt {
+                    bit_size: 128,
+                    signed: true,
+                },
+            ),
+            (
+                "u128",
+                NodeContent::RecInt {
+                    bit_size: 128,
+                    signed: false,
+                },
+            ),
             ("f32", NodeContent::RecFloat { size: 32 }),
             ("f64", NodeContent::RecFloat { size: 64 }),
+            ("String", NodeContent::RecString),
+            ("char", NodeContent::RecChar),
         ];
 
         for native in natives.iter() {
@@ -1012,9 +1449,23 @@ impl NodeTree {
         // Create boolean type using enumeration
         let node_bool_enum = bool::recipe(self);
         self.child(node, node_bool_enum);
+        // Create duration type using structure
+        let node_duration = Duration::recipe(self);
+        self.child(node, node_duration);
         // Create generic types
         self.create_generic_type(Some(node), "List", NodeContent::RecList, 1);
-        self.create_generic_type(Some(node), "Map", NodeContent::RecMap, 2);
+        self.create_generic_type(Some(node), "Set", NodeContent::RecSet, 1);
+        self.create_generic_type(Some(node), "Map", NodeContent::RecMap { sorted: false }, 2);
+        self.create_generic_type(
+            Some(node),
+            "SortedMap",
+            NodeContent::RecMap { sorted: true },
+            2,
+        );
+        self.create_generic_type(Some(node), "Option", NodeContent::RecOption, 1);
+        self.create_generic_type(Some(node), "Box", NodeContent::RecBox, 1);
+        self.create_generic_type(Some(node), "Rc", NodeContent::RecBox, 1);
+        self.create_generic_type(Some(node), "Arc", NodeContent::RecBox, 1);
     }
 }
 
@@ -1036,22 +1487,123 @@ fn int_bounds(bit_size: u32, signed: bool) -> (BigInt, BigInt) {
     )
 }
 
-enum WriteIntCheckBoundsError {
-    IOError(std::io::Error),
-    OutOfBounds,
-}
-
-impl From<std::io::Error> for WriteIntCheckBoundsError {
-    fn from(e: std::io::Error) -> Self {
-        WriteIntCheckBoundsError::IOError(e)
-    }
-}
-
-/// Write an integer to a stream, or return an error if value is out of bounds.
+/// Decode an integer literal, which can have a sign, a radix prefix (`0x`, `0o` or `0b`) and `_`
+/// separators.
+///
+/// The grammar guarantees the literal is well formed.
 ///
 /// # Arguments
 ///
-/// * `wr` - Output stream
+/// * `repr` - Integer literal
+fn parse_int_literal(repr: &str) -> BigInt {
+    let (negative, digits) = if let Some(digits) = repr.strip_prefix('-') {
+        (true, digits)
+    } else {
+        (false, repr.strip_prefix('+').unwrap_or(repr))
+    };
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits),
+    };
+    let digits: String = digits.chars().filter(|&c| c != '_').collect();
+    let value = BigInt::parse_bytes(digits.as_bytes(), radix).unwrap();
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Decode a string or character literal as written in a data file, or return None if an escape
+/// sequence does not designate a valid unicode scalar value.
+///
+/// The grammar guarantees the literal is well formed: either a quoted string or character with
+/// escape sequences, or a raw string delimited by `r#"` and `"#` with any number of `#`.
+///
+/// # Arguments
+///
+/// * `repr` - String literal, including the quotes.
+fn parse_string_literal(repr: &str) -> Option<String> {
+    if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return Some(raw[hashes + 1..raw.len() - hashes - 1].to_string());
+    }
+    let mut result = String::new();
+    let mut chars = repr[1..repr.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        result.push(match chars.next().unwrap() {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                std::char::from_u32(u32::from_str_radix(&code, 16).unwrap())?
+            }
+            // Remaining escapes are `\\`, `\"` and `\'`
+            c => c,
+        });
+    }
+    Some(result)
+}
+
+/// Edit distance between two strings, in characters. Insertions, deletions, substitutions and
+/// transpositions of adjacent characters count as one edit each, so that common typos such as
+/// `widht` are close to the intended word.
+///
+/// # Arguments
+///
+/// * `a` - First string
+/// * `b` - Second string
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // d[i][j] is the distance between the first i characters of a and the first j of b.
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+enum WriteIntCheckBoundsError {
+    IOError(std::io::Error),
+    OutOfBounds,
+}
+
+impl From<std::io::Error> for WriteIntCheckBoundsError {
+    fn from(e: std::io::Error) -> Self {
+        WriteIntCheckBoundsError::IOError(e)
+    }
+}
+
+/// Write an integer to a stream, or return an error if value is out of bounds.
+///
+/// The integer is written using the minimum number of whole bytes for its bit size.
+///
+/// # Arguments
+///
+/// * `wr` - Output stream
 /// * `bit_size` - Size in bits of the integer
 /// * `signed` - Wheter the integer is signed or not
 fn write_int_check_bounds(
@@ -1067,38 +1619,91 @@ fn write_int_check_bounds(
             let negative = value.sign() == Sign::Minus;
             // Extend the sign
             // As we are writing little-endian, we push the bytes after.
-            while bytes.len() < (bit_size / 8) as usize {
+            while bytes.len() < bit_size.div_ceil(8) as usize {
                 bytes.push(if negative { 0xff } else { 0 });
             }
             bytes
         } else {
             let mut bytes = value.to_bytes_le().1;
-            while bytes.len() < (bit_size / 8) as usize {
+            while bytes.len() < bit_size.div_ceil(8) as usize {
                 bytes.push(0);
             }
             bytes
         };
-        wr.write(bytes.as_slice())?;
+        wr.write_all(bytes.as_slice())?;
         Ok(())
     } else {
         Err(WriteIntCheckBoundsError::OutOfBounds)
     }
 }
 
+/// Ordering key of a decoded value, used to sort map and set entries
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Int(BigInt),
+    Bytes(Vec<u8>),
+    Seq(Vec<SortKey>),
+}
+
+/// Output stream of the compiler
+///
+/// Written bytes can be captured in memory instead of being sent to the destination stream, so
+/// they can be inspected before being written. Captures can be nested.
+struct Output<'a> {
+    io: &'a mut dyn std::io::Write,
+    captures: Vec<Vec<u8>>,
+}
+
+impl Output<'_> {
+    /// Start capturing written bytes.
+    fn begin_capture(&mut self) {
+        self.captures.push(Vec::new());
+    }
+
+    /// Stop the last started capture and return the captured bytes.
+    fn end_capture(&mut self) -> Vec<u8> {
+        self.captures.pop().unwrap()
+    }
+}
+
+impl std::io::Write for Output<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.captures.last_mut() {
+            Some(capture) => {
+                capture.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            None => self.io.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.io.flush()
+    }
+}
+
 struct Compiler<'a> {
     tree: NodeTree,
-    io: &'a mut dyn std::io::Write,
+    io: Output<'a>,
     errors: Vec<CompilationError>,
     generic_stack: Vec<Vec<u32>>,
+    options: LoadOptions,
+    /// Recipe files loaded by imports
+    imports: Vec<PathBuf>,
 }
 
 impl Compiler<'_> {
     pub fn new<'a>(io: &'a mut dyn std::io::Write) -> Compiler<'a> {
         Compiler {
             tree: NodeTree::new(),
-            io: io,
+            io: Output {
+                io,
+                captures: Vec::new(),
+            },
             errors: Vec::new(),
             generic_stack: Vec::new(),
+            options: LoadOptions::default(),
+            imports: Vec::new(),
         }
     }
 
@@ -1107,6 +1712,143 @@ impl Compiler<'_> {
         self.errors.push(err);
     }
 
+    /// Return `LoadError::CompilationErrors` with the diagnostics of declared errors if there is
+    /// any.
+    fn check_errors(&self) -> Result<(), LoadError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(LoadError::CompilationErrors(
+                self.errors
+                    .iter()
+                    .map(|e| e.diagnostic(&self.tree))
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Walks the tree and load all imported recipe files
+    ///
+    /// Each `RecImport` node is turned into a `RecMod` node named after the imported file, without
+    /// extension, and the declarations of the file are parsed as children of this module.
+    ///
+    /// Imported files are searched relative to the directory of the importing file first, then in
+    /// the search paths of the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid` - Node to be processed. Children nodes are processed recursively.
+    /// * `import_stack` - Canonical paths of the files being imported, used to detect cycles.
+    fn resolve_imports(
+        &mut self,
+        nid: u32,
+        import_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), LoadError> {
+        let NodeContent::RecImport { path: repr } = self.tree.get(nid).content.clone() else {
+            for child in self.tree.children(nid).clone() {
+                self.resolve_imports(child, import_stack)?;
+            }
+            return Ok(());
+        };
+
+        let Some(path) = parse_string_literal(&repr) else {
+            self.error(CompilationError::InvalidEscapeSequence(nid));
+            return Ok(());
+        };
+        let location = self.tree.get(nid).source.as_ref().unwrap();
+        let base_dir = location.path().and_then(Path::parent);
+        let Some(file_path) = base_dir
+            .into_iter()
+            .chain(self.options.search_paths.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&path))
+            .find(|p| p.is_file())
+        else {
+            self.error(CompilationError::ImportNotFound { path, node: nid });
+            return Ok(());
+        };
+        let canonical_path = file_path.canonicalize()?;
+        if import_stack.contains(&canonical_path) {
+            self.error(CompilationError::ImportCycle { path, node: nid });
+            return Ok(());
+        }
+
+        let mut rec = String::new();
+        File::open(&file_path)?.read_to_string(&mut rec)?;
+        if !self.imports.contains(&canonical_path) {
+            self.imports.push(canonical_path.clone());
+        }
+        let node = self.tree.get_mut(nid);
+        node.name = Some(
+            file_path
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned(),
+        );
+        node.content = NodeContent::RecMod;
+        self.tree
+            .parse_mod_source(Source::new(Some(&file_path), &rec), nid)?;
+
+        // Imports of the imported file
+        import_stack.push(canonical_path);
+        for child in self.tree.children(nid).clone() {
+            self.resolve_imports(child, import_stack)?;
+        }
+        import_stack.pop();
+        Ok(())
+    }
+
+    /// Walks the tree and report type aliases referring to themselves, directly or through other
+    /// aliases. Such aliases would be expanded endlessly.
+    ///
+    /// Must be called after type resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid` - Node to be checked. Children nodes are checked recursively.
+    fn check_alias_cycles(&mut self, nid: u32) {
+        if let NodeContent::RecAlias = self.tree.get(nid).content {
+            let mut visited = Vec::new();
+            if self.alias_refers_to(nid, nid, &mut visited) {
+                self.error(CompilationError::AliasCycle(nid));
+            }
+        }
+        for child_id in self.tree.children(nid).clone() {
+            self.check_alias_cycles(child_id);
+        }
+    }
+
+    /// Return true if a type expression refers to the given alias, following the aliases it
+    /// refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `nid` - Type expression node
+    /// * `alias_nid` - Searched alias node
+    /// * `visited` - Aliases already followed, to stop on cycles not involving `alias_nid`
+    fn alias_refers_to(&self, nid: u32, alias_nid: u32, visited: &mut Vec<u32>) -> bool {
+        if let NodeContent::RecTypeInst {
+            tid: RecTypeId::Id(target),
+        } = self.tree.get(nid).content
+        {
+            if target == alias_nid {
+                return true;
+            }
+            if let NodeContent::RecAlias = self.tree.get(target).content {
+                if !visited.contains(&target) {
+                    visited.push(target);
+                    if self.alias_refers_to(target, alias_nid, visited) {
+                        return true;
+                    }
+                }
+            }
+        }
+        self.tree
+            .children(nid)
+            .iter()
+            .any(|&child| self.alias_refers_to(child, alias_nid, visited))
+    }
+
     /// Walks the tree and resolve all types
     ///
     /// All `RecTypeId` are resolved to their corresponding node Id.
@@ -1122,21 +1864,26 @@ impl Compiler<'_> {
         match node.content.clone() {
             NodeContent::RecInt { .. }
             | NodeContent::RecFloat { .. }
-            | NodeContent::RecList
-            | NodeContent::RecMap => {}
-            NodeContent::RecStruct | NodeContent::RecTuple | NodeContent::RecStructMember => {
+            | NodeContent::RecString
+            | NodeContent::RecChar => {}
+            // Native generic types only have `RecGeneric` children, but in derived recipes the
+            // item types are children of the generic type node and must be resolved too.
+            NodeContent::RecList
+            | NodeContent::RecSet
+            | NodeContent::RecMap { .. }
+            | NodeContent::RecOption
+            | NodeContent::RecBox
+            | NodeContent::RecStruct
+            | NodeContent::RecMod
+            | NodeContent::RecTuple
+            | NodeContent::RecArray { .. }
+            | NodeContent::RecStructMember
+            | NodeContent::RecTupleMember
+            | NodeContent::RecAlias => {
                 for child_id in self.tree.children(nid).clone() {
                     self.resolve_types(child_id);
                 }
             }
-            NodeContent::RecTupleMember { tid } => {
-                let resolved_tid = self.resolve_type_id(tid.clone(), nid);
-                if let NodeContent::RecTupleMember { tid } = &mut self.tree.get_mut(nid).content {
-                    *tid = resolved_tid;
-                } else {
-                    panic!();
-                }
-            }
             NodeContent::RecEnum { key_type: tid } => {
                 let resolved_tid = self.resolve_type_id(tid.clone(), nid);
                 if let NodeContent::RecEnum { key_type: tid } = &mut self.tree.get_mut(nid).content
@@ -1149,17 +1896,31 @@ impl Compiler<'_> {
                 if let RecTypeId::Id(rec_type_id) = resolved_tid {
                     let rec_type = self.tree.get(rec_type_id);
                     if let NodeContent::RecInt { bit_size, signed } = rec_type.content {
-                        // Now calculate the values of the enumeration items
+                        // Now calculate the values of the enumeration items. Items without
+                        // explicit value follow the previous item.
+                        let (min, max) = int_bounds(bit_size, signed);
                         let mut next_value = BigInt::from(0);
+                        let mut values: Vec<(BigInt, u32)> = Vec::new();
                         for child_id in self.tree.children(nid).clone() {
                             let child = self.tree.get_mut(child_id);
-                            if let NodeContent::RecEnumItem { value } = &mut child.content {
-                                let (min, max) = int_bounds(bit_size, signed);
-                                if (next_value >= min) && (next_value <= max) {
+                            if let NodeContent::RecEnumItem { value, explicit } = &mut child.content
+                            {
+                                if !*explicit {
                                     *value = next_value.clone();
-                                    next_value = next_value + 1;
-                                } else {
+                                }
+                                let value = value.clone();
+                                next_value = &value + 1;
+                                if (value < min) || (value > max) {
                                     self.error(CompilationError::EnumValueOutOfBounds(child_id));
+                                } else if let Some((_, first)) =
+                                    values.iter().find(|(v, _)| *v == value)
+                                {
+                                    self.error(CompilationError::EnumDuplicateValue {
+                                        first: *first,
+                                        second: child_id,
+                                    });
+                                } else {
+                                    values.push((value, child_id));
                                 }
                                 // Resolve data type if defined
                                 if let Some(data_type_node) =
@@ -1167,7 +1928,7 @@ impl Compiler<'_> {
                                 {
                                     self.resolve_types(data_type_node);
                                 }
-                            } else {
+                            } else if !matches!(child.content, NodeContent::RecGeneric { .. }) {
                                 panic!();
                             }
                         }
@@ -1195,7 +1956,7 @@ impl Compiler<'_> {
                     let current_arg_count = children.len();
                     if current_arg_count != expected_arg_count {
                         self.error(CompilationError::GenericArgCountMismatch {
-                            nid: nid,
+                            nid,
                             expected: expected_arg_count,
                             current: current_arg_count,
                         });
@@ -1204,14 +1965,19 @@ impl Compiler<'_> {
             }
             NodeContent::RecEnumItem { .. }
             | NodeContent::RecGeneric { .. }
+            | NodeContent::RecImport { .. }
             | NodeContent::DatMap
             | NodeContent::DatTuple
             | NodeContent::DatList
+            | NodeContent::DatSet
             | NodeContent::DatEnum
             | NodeContent::DatMapAssignment
             | NodeContent::DatTupleMember
             | NodeContent::DatInt { .. }
-            | NodeContent::DatFloat { .. } => {}
+            | NodeContent::DatFloat { .. }
+            | NodeContent::DatString { .. }
+            | NodeContent::DatChar { .. }
+            | NodeContent::DatBinary { .. } => {}
         }
     }
 
@@ -1224,7 +1990,10 @@ impl Compiler<'_> {
     fn resolve_type_id(&mut self, tid: RecTypeId, nid: u32) -> RecTypeId {
         match &tid {
             RecTypeId::Path(path) => {
-                if let Some(id) = self.resolve_typename(nid, &path) {
+                if let Some(id) = self
+                    .resolve_typename(nid, path)
+                    .or_else(|| self.create_int_type(nid, path))
+                {
                     RecTypeId::Id(id)
                 } else {
                     self.error(CompilationError::UnresolvedType {
@@ -1238,60 +2007,149 @@ impl Compiler<'_> {
         }
     }
 
+    /// Create the integer type designated by a name such as `u24` or `i48`, and return its node
+    /// Id, or None if the name does not designate an integer type.
+    ///
+    /// Any width from 1 to 128 bits is allowed, so these types cannot all be registered as
+    /// natives. The type is declared in the root scope, so it is created only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Current recipe scope node
+    /// * `typename` - Name of the type
+    fn create_int_type(&mut self, scope: u32, typename: &str) -> Option<u32> {
+        let (signed, width) = if let Some(width) = typename.strip_prefix('i') {
+            (true, width)
+        } else {
+            (false, typename.strip_prefix('u')?)
+        };
+        if !width.bytes().all(|c| c.is_ascii_digit()) || width.starts_with('0') {
+            return None;
+        }
+        let bit_size: u32 = width.parse().ok()?;
+        if !(1..=128).contains(&bit_size) {
+            return None;
+        }
+        let mut root = scope;
+        while let Some(parent) = *self.tree.get_item(root).parent() {
+            root = parent;
+        }
+        Some(self.tree.create_with_parent(
+            Some(root),
+            Node::new_builtin(typename, NodeContent::RecInt { bit_size, signed }),
+        ))
+    }
+
     /// Solve a typename and return corresponding recipe type node Id
     ///
+    /// The typename can be a path such as `math::Vec3`. The first element of the path is searched
+    /// in the current scope and its parents, the following elements are searched in the
+    /// declarations of the previous one.
+    ///
     /// # Arguments
     ///
     /// * `scope` - Current recipe scope node
     /// * `typename` - Searched typename
-    fn resolve_typename(&self, scope: u32, typename: &String) -> Option<u32> {
+    fn resolve_typename(&self, scope: u32, typename: &str) -> Option<u32> {
+        let mut names = typename.split("::");
+        let mut nid = self.resolve_name(scope, names.next().unwrap())?;
+        for name in names {
+            nid = *self.tree.children(nid).iter().find(|&&a| {
+                let node = self.tree.get(a);
+                node.name.as_deref() == Some(name)
+                    && matches!(
+                        node.content,
+                        NodeContent::RecStruct
+                            | NodeContent::RecEnum { .. }
+                            | NodeContent::RecAlias
+                            | NodeContent::RecMod
+                    )
+            })?;
+        }
+        // Modules are namespaces, not types.
+        if let NodeContent::RecMod = self.tree.get(nid).content {
+            None
+        } else {
+            Some(nid)
+        }
+    }
+
+    /// Search a name in a recipe scope and its parents, and return the corresponding node Id
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Current recipe scope node
+    /// * `name` - Searched name
+    fn resolve_name(&self, scope: u32, name: &str) -> Option<u32> {
         let scope_node = self.tree.get_item(scope);
         match scope_node.value.content {
-            NodeContent::RecStructMember { .. } | NodeContent::RecTupleMember { .. } => {
+            NodeContent::RecStructMember | NodeContent::RecTupleMember => {
                 match scope_node.parent() {
-                    Some(id) => self.resolve_typename(*id, typename),
+                    Some(id) => self.resolve_name(*id, name),
                     None => None,
                 }
             }
-            NodeContent::RecStruct => {
+            NodeContent::RecStruct | NodeContent::RecMod => {
                 match self
                     .tree
                     .children(scope)
                     .iter()
-                    .find(|&&a| self.tree.get(a).name == Some(typename.clone()))
+                    .find(|&&a| self.tree.get(a).name.as_deref() == Some(name))
                     .copied()
                 {
                     Some(node) => Some(node),
                     // Search in parent
                     None => match scope_node.parent() {
-                        Some(id) => self.resolve_typename(*id, typename),
+                        Some(id) => self.resolve_name(*id, name),
                         None => None,
                     },
                 }
             }
             NodeContent::RecTuple
+            | NodeContent::RecArray { .. }
             | NodeContent::RecTypeInst { .. }
             | NodeContent::RecEnumItem { .. } => {
                 // Search in parent
-                self.resolve_typename(scope_node.parent().unwrap(), typename)
+                self.resolve_name(scope_node.parent().unwrap(), name)
+            }
+            NodeContent::RecEnum { .. } | NodeContent::RecAlias => {
+                // Enumeration values are not types and aliases only declare generic types, so
+                // only generic types can be found here.
+                match self.tree.children(scope).iter().find(|&&a| {
+                    let node = self.tree.get(a);
+                    node.name.as_deref() == Some(name)
+                        && matches!(node.content, NodeContent::RecGeneric { .. })
+                }) {
+                    Some(&node) => Some(node),
+                    None => match scope_node.parent() {
+                        Some(id) => self.resolve_name(*id, name),
+                        None => None,
+                    },
+                }
             }
-            NodeContent::RecEnum { .. } => match scope_node.parent() {
-                Some(id) => self.resolve_typename(*id, typename),
-                None => None,
-            },
             NodeContent::RecInt { .. }
             | NodeContent::RecFloat { .. }
+            | NodeContent::RecString
+            | NodeContent::RecChar
             | NodeContent::RecList
-            | NodeContent::RecMap
+            | NodeContent::RecSet
+            | NodeContent::RecMap { .. }
+            | NodeContent::RecOption
+            | NodeContent::RecBox
             | NodeContent::RecGeneric { .. } => None,
-            NodeContent::DatMap
+            NodeContent::RecImport { .. }
+            | NodeContent::DatMap
             | NodeContent::DatMapAssignment
             | NodeContent::DatTupleMember
             | NodeContent::DatInt { .. }
             | NodeContent::DatFloat { .. }
+            | NodeContent::DatString { .. }
+            | NodeContent::DatChar { .. }
+            | NodeContent::DatBinary { .. }
             | NodeContent::DatEnum
             | NodeContent::DatTuple
-            | NodeContent::DatList => {
+            | NodeContent::DatList
+            | NodeContent::DatSet => {
                 panic!()
             }
         }
@@ -1311,11 +2169,35 @@ impl Compiler<'_> {
             NodeContent::RecFloat { size } => {
                 self.write_float(dat_node, size)?;
             }
+            NodeContent::RecString => {
+                self.write_string(dat_node)?;
+            }
+            NodeContent::RecChar => {
+                self.write_char(dat_node)?;
+            }
             NodeContent::RecList => {
                 self.write_list(rec_node, dat_node)?;
             }
-            NodeContent::RecMap => {
-                self.write_map(rec_node, dat_node)?;
+            NodeContent::RecMap { sorted } => {
+                self.write_map(rec_node, dat_node, sorted)?;
+            }
+            NodeContent::RecSet => {
+                self.write_set(rec_node, dat_node)?;
+            }
+            NodeContent::RecOption => {
+                self.write_option(rec_node, dat_node)?;
+            }
+            NodeContent::RecBox => {
+                let item_type_nid = self.tree.unique_child(rec_node);
+                self.write(item_type_nid, dat_node)?;
+            }
+            NodeContent::RecAlias => {
+                // Generic arguments of the alias have been pushed by the type instantiation.
+                let type_nid = *self.tree.children(rec_node).last().unwrap();
+                self.write(type_nid, dat_node)?;
+            }
+            NodeContent::RecArray { len } => {
+                self.write_array(rec_node, dat_node, len)?;
             }
             NodeContent::RecStruct => {
                 self.write_struct(rec_node, dat_node)?;
@@ -1324,14 +2206,17 @@ impl Compiler<'_> {
                 self.write_tuple(rec_node, dat_node)?
             }
             NodeContent::RecStructMember => {
-                let type_nid = self.tree.get_item(rec_node).unique_child();
+                let type_nid = self.tree.children(rec_node)[0];
                 self.write_struct_member(rec_node, dat_node, type_nid)?;
             }
             NodeContent::RecEnum { key_type: tid } => {
                 self.write_enum(rec_node, dat_node, tid.unwrap_id())?;
             }
             NodeContent::RecTypeInst { tid } => {
-                let may_be_generic = self.tree.get(tid.unwrap_id()).content.may_be_generic();
+                // Types without generic parameters don't get a generic stack level, so the
+                // generic types of an enclosing generic structure remain visible from types
+                // declared inside it.
+                let may_be_generic = self.tree.number_of_generic_types(tid.unwrap_id()) > 0;
                 if may_be_generic {
                     let generics = self.tree.children(rec_node).clone();
                     self.generic_stack.push(generics);
@@ -1343,22 +2228,34 @@ impl Compiler<'_> {
                 write_result?;
             }
             NodeContent::RecGeneric { index } => {
-                let current_generics = self.generic_stack.last().unwrap().clone();
-                let type_nid = current_generics.get(index as usize).unwrap();
-                self.write(*type_nid, dat_node)?;
+                // Generic arguments are defined in the scope of the type instantiation, so they
+                // may refer to the generic types of the enclosing scope. Pop the current generics
+                // while writing the argument.
+                let current_generics = self.generic_stack.pop().unwrap();
+                let type_nid = current_generics[index as usize];
+                let write_result = self.write(type_nid, dat_node);
+                self.generic_stack.push(current_generics);
+                write_result?;
             }
             // RecEnumItem written during write_enum, so this case cannot happen
             NodeContent::RecEnumItem { .. }
+            // Modules are not types, type resolution never returns them
+            | NodeContent::RecMod
+            | NodeContent::RecImport { .. }
             // RecTupleMember written during write_tuple, so this case cannot happen
-            | NodeContent::RecTupleMember { .. }
+            | NodeContent::RecTupleMember
             | NodeContent::DatMap
             | NodeContent::DatMapAssignment
             | NodeContent::DatTupleMember
             | NodeContent::DatInt { .. }
             | NodeContent::DatFloat { .. }
+            | NodeContent::DatString { .. }
+            | NodeContent::DatChar { .. }
+            | NodeContent::DatBinary { .. }
             | NodeContent::DatEnum
             | NodeContent::DatTuple
-            | NodeContent::DatList => {
+            | NodeContent::DatList
+            | NodeContent::DatSet => {
                 panic!();
             }
         }
@@ -1379,8 +2276,7 @@ impl Compiler<'_> {
             // We use a BigInt since the value in the input file can have any number of digits, and
             // we want to be able to check the bounds of this value properly. Using BigInt makes
             // this easy, though this might not be the most fast/optimal way.
-            // The following parsing shall never fail if the grammar is correct.
-            let int = BigInt::parse_bytes(repr.as_bytes(), 10).unwrap();
+            let int = parse_int_literal(repr);
             match write_int_check_bounds(&mut self.io, bit_size, signed, &int) {
                 Ok(_) => {}
                 Err(_) => {
@@ -1401,74 +2297,537 @@ impl Compiler<'_> {
     /// * `bit_size` - 32 for f32, 64 for f64
     fn write_float(&mut self, dat_nid: u32, bit_size: u32) -> WriteResult {
         // Data node can be either DatFloat or DatInt.
-        if let NodeContent::DatInt { repr } | NodeContent::DatFloat { repr } =
-            &self.tree.get(dat_nid).content
-        {
+        let repr = match &self.tree.get(dat_nid).content {
+            NodeContent::DatFloat { repr } => Some(repr.clone()),
+            // Integers are converted to decimal first, as float parsing does not support radix
+            // prefixes and separators.
+            NodeContent::DatInt { repr } => Some(parse_int_literal(repr).to_string()),
+            _ => None,
+        };
+        if let Some(repr) = repr {
             match bit_size {
                 32 => {
                     if let Ok(f) = repr.parse::<f32>() {
-                        self.io.write(&f.to_le_bytes())?;
-                        Ok(())
+                        self.io.write_all(&f.to_le_bytes())?;
                     } else {
-                        panic!();
+                        self.error(CompilationError::InvalidFloat(dat_nid));
                     }
+                    Ok(())
                 }
                 64 => {
                     if let Ok(f) = repr.parse::<f64>() {
-                        self.io.write(&f.to_le_bytes())?;
-                        Ok(())
+                        self.io.write_all(&f.to_le_bytes())?;
                     } else {
-                        panic!();
+                        self.error(CompilationError::InvalidFloat(dat_nid));
                     }
+                    Ok(())
                 }
                 _ => {
                     panic!();
                 }
             }
-        } else {
-            self.error(CompilationError::ExpectedDatFloat(dat_nid));
-            Ok(())
-        }
-    }
-
-    /// Write given data node as given List node
-    ///
-    /// # Arguments
-    ///
-    /// * `rec_nid` - Id of the recipe structure node
-    /// * `dat_nid` - Id of the data node
-    fn write_list(&mut self, rec_nid: u32, dat_nid: u32) -> WriteResult {
-        let item_type_nid = self.tree.unique_child(rec_nid);
-        let items = self.tree.children(dat_nid).clone();
-        let bytes = items.len().to_le_bytes();
-        self.io.write(&bytes)?;
-        //ctx.io.write(&items.len().to_le_bytes())?;  // Write size of list
-        for &item_nid in items.iter() {
-            self.write(item_type_nid, item_nid)?
+        } else {
+            self.error(CompilationError::ExpectedDatFloat(dat_nid));
+            Ok(())
+        }
+    }
+
+    /// Write given data node as a string
+    ///
+    /// The string is written as its length in bytes on 64 bits, followed by its UTF-8 encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `dat_nid` - Data node Id
+    fn write_string(&mut self, dat_nid: u32) -> WriteResult {
+        if let NodeContent::DatString { repr } = &self.tree.get(dat_nid).content {
+            if let Some(value) = parse_string_literal(repr) {
+                self.io.write_all(&(value.len() as u64).to_le_bytes())?;
+                self.io.write_all(value.as_bytes())?;
+            } else {
+                self.error(CompilationError::InvalidEscapeSequence(dat_nid));
+            }
+        } else {
+            self.error(CompilationError::ExpectedDatString(dat_nid));
+        }
+        Ok(())
+    }
+
+    /// Write given data node as a character, encoded in UTF-8 as bincode does
+    ///
+    /// # Arguments
+    ///
+    /// * `dat_nid` - Data node Id
+    fn write_char(&mut self, dat_nid: u32) -> WriteResult {
+        if let NodeContent::DatChar { repr } = &self.tree.get(dat_nid).content {
+            // The grammar guarantees the literal is a single character or escape sequence.
+            if let Some(value) = parse_string_literal(repr) {
+                self.io.write_all(value.as_bytes())?;
+            } else {
+                self.error(CompilationError::InvalidEscapeSequence(dat_nid));
+            }
+        } else {
+            self.error(CompilationError::ExpectedDatChar(dat_nid));
+        }
+        Ok(())
+    }
+
+    /// Write given data node as given List node
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe structure node
+    /// * `dat_nid` - Id of the data node
+    fn write_list(&mut self, rec_nid: u32, dat_nid: u32) -> WriteResult {
+        if !matches!(self.tree.get(dat_nid).content, NodeContent::DatList) {
+            self.error(CompilationError::ExpectedDatList(dat_nid));
+            return Ok(());
+        }
+        let item_type_nid = self.tree.unique_child(rec_nid);
+        let items = self.tree.children(dat_nid).clone();
+        let bytes = items.len().to_le_bytes();
+        self.io.write_all(&bytes)?;
+        for &item_nid in items.iter() {
+            self.write(item_type_nid, item_nid)?
+        }
+        Ok(())
+    }
+
+    /// Write given data node as given array node
+    ///
+    /// Data must be a list with exactly `len` items. No length prefix is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe array node
+    /// * `dat_nid` - Id of the data node
+    /// * `len` - Array length
+    fn write_array(&mut self, rec_nid: u32, dat_nid: u32, len: usize) -> WriteResult {
+        if let NodeContent::DatList = self.tree.get(dat_nid).content {
+            let item_type_nid = self.tree.unique_child(rec_nid);
+            let items = self.tree.children(dat_nid).clone();
+            if items.len() != len {
+                self.error(CompilationError::ArraySizeMismatch {
+                    node_array: rec_nid,
+                    node_data: dat_nid,
+                    expected: len,
+                });
+                return Ok(());
+            }
+            for &item_nid in items.iter() {
+                self.write(item_type_nid, item_nid)?
+            }
+        } else {
+            self.error(CompilationError::ExpectedDatList(dat_nid));
+        }
+        Ok(())
+    }
+
+    /// Report an error for each member defined in the data which is not declared in the recipe
+    /// structure, as it is most likely a typo.
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe structure node
+    /// * `dat_nid` - Id of the data node. Must be a `DatMap` with identifiers as keys.
+    fn check_unknown_members(&mut self, rec_nid: u32, dat_nid: u32) {
+        let members: Vec<String> = self
+            .tree
+            .children(rec_nid)
+            .iter()
+            .map(|&nid| self.tree.get(nid))
+            .filter(|node| matches!(node.content, NodeContent::RecStructMember))
+            .filter_map(|node| node.name.clone())
+            .collect();
+        for assignment in self.tree.children(dat_nid).clone() {
+            let key_nid = self.tree.children(assignment)[0];
+            let name = self.tree.get(key_nid).name.clone().unwrap();
+            if members.contains(&name) {
+                continue;
+            }
+            // Suggest the closest members, allowing roughly one typo every three characters.
+            let max_distance = (name.chars().count() / 3).max(1);
+            let mut candidates: Vec<(usize, &String)> = members
+                .iter()
+                .map(|member| (edit_distance(&name, member), member))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .collect();
+            // Stable sort, so members with the same distance keep the declaration order.
+            candidates.sort_by_key(|(distance, _)| *distance);
+            self.error(CompilationError::UnknownMember {
+                node: key_nid,
+                node_struct: rec_nid,
+                suggestions: candidates.into_iter().map(|(_, m)| m.clone()).collect(),
+            });
+        }
+    }
+
+    /// Write given data node as given Map node
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe structure node
+    /// * `dat_nid` - Id of the data node
+    /// * `sorted` - Whether entries must be sorted by key value
+    fn write_map(&mut self, rec_nid: u32, dat_nid: u32, sorted: bool) -> WriteResult {
+        let generic_args = self.tree.children(rec_nid).clone();
+        assert_eq!(generic_args.len(), 2);
+        if !matches!(self.tree.get(dat_nid).content, NodeContent::DatMap) {
+            self.error(CompilationError::ExpectedDatMap(dat_nid));
+            return Ok(());
+        }
+        let items = self.tree.children(dat_nid).clone();
+        let bytes = items.len().to_le_bytes();
+        self.io.write_all(&bytes)?;
+        let sorted = sorted || self.options.sort_maps;
+        // Keys are compared using their binary representation, so different notations of the
+        // same value are detected as duplicates.
+        let mut keys = HashMap::<Vec<u8>, u32>::new();
+        let mut entries = Vec::<(Vec<u8>, Vec<u8>)>::new();
+        let error_count = self.errors.len();
+        for &item_nid in items.iter() {
+            let item_children = self.tree.children(item_nid).clone();
+            assert_eq!(item_children.len(), 2);
+            let key_error_count = self.errors.len();
+            self.io.begin_capture();
+            let result = self.write(generic_args[0], item_children[0]);
+            let key = self.io.end_capture();
+            result?;
+            if self.errors.len() > key_error_count {
+                // Invalid key, its representation is meaningless.
+            } else if let Some(&first) = keys.get(&key) {
+                self.error(CompilationError::DuplicateMapKey {
+                    first,
+                    second: item_children[0],
+                });
+            } else {
+                keys.insert(key.clone(), item_children[0]);
+            }
+            if sorted {
+                self.io.begin_capture();
+                let result = self.write(generic_args[1], item_children[1]);
+                let value = self.io.end_capture();
+                result?;
+                entries.push((key, value));
+            } else {
+                self.io.write_all(&key)?;
+                self.write(generic_args[1], item_children[1])?
+            }
+        }
+        // Keys can only be decoded if they have all been written successfully. Otherwise the
+        // output is discarded anyway.
+        if sorted && self.errors.len() == error_count {
+            let key_type_nid = generic_args[0];
+            let mut sort_keys = Vec::new();
+            for (key, _) in entries.iter() {
+                sort_keys.push(self.sort_key(key_type_nid, &mut key.as_slice()));
+            }
+            let mut order: Vec<usize> = (0..entries.len()).collect();
+            order.sort_by(|&a, &b| sort_keys[a].cmp(&sort_keys[b]));
+            entries = order.into_iter().map(|i| entries[i].clone()).collect();
+        }
+        for (key, value) in entries {
+            self.io.write_all(&key)?;
+            self.io.write_all(&value)?;
+        }
+        Ok(())
+    }
+
+    /// Write given data node as given Set node
+    ///
+    /// Items are sorted by value, so identical sets always produce identical output.
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe set node
+    /// * `dat_nid` - Id of the data node
+    fn write_set(&mut self, rec_nid: u32, dat_nid: u32) -> WriteResult {
+        let item_type_nid = self.tree.unique_child(rec_nid);
+        let items = self.tree.children(dat_nid).clone();
+        match self.tree.get(dat_nid).content {
+            NodeContent::DatSet => {}
+            // `{}` is parsed as an empty map
+            NodeContent::DatMap if items.is_empty() => {}
+            _ => {
+                self.error(CompilationError::ExpectedDatSet(dat_nid));
+                return Ok(());
+            }
+        }
+        let bytes = items.len().to_le_bytes();
+        self.io.write_all(&bytes)?;
+        // Items are compared using their binary representation, so different notations of the
+        // same value are detected as duplicates.
+        let mut values = HashMap::<Vec<u8>, u32>::new();
+        let set_error_count = self.errors.len();
+        for &item_nid in items.iter() {
+            let error_count = self.errors.len();
+            self.io.begin_capture();
+            let result = self.write(item_type_nid, item_nid);
+            let value = self.io.end_capture();
+            result?;
+            if self.errors.len() > error_count {
+                // Invalid item, its representation is meaningless.
+            } else if let Some(&first) = values.get(&value) {
+                self.error(CompilationError::DuplicateSetItem {
+                    first,
+                    second: item_nid,
+                });
+            } else {
+                values.insert(value, item_nid);
+            }
+        }
+        let mut values: Vec<Vec<u8>> = values.into_keys().collect();
+        // Items can only be decoded if they have all been written successfully. Otherwise the
+        // output is discarded anyway.
+        if self.errors.len() == set_error_count {
+            let mut sort_keys = Vec::new();
+            for value in values.iter() {
+                sort_keys.push(self.sort_key(item_type_nid, &mut value.as_slice()));
+            }
+            let mut order: Vec<usize> = (0..values.len()).collect();
+            order.sort_by(|&a, &b| sort_keys[a].cmp(&sort_keys[b]));
+            values = order.into_iter().map(|i| values[i].clone()).collect();
+        }
+        for value in values {
+            self.io.write_all(&value)?;
+        }
+        Ok(())
+    }
+
+    /// Write given data node as given Option node
+    ///
+    /// Data must be either `None` or `Some(value)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Id of the recipe option node
+    /// * `dat_nid` - Id of the data node
+    fn write_option(&mut self, rec_nid: u32, dat_nid: u32) -> WriteResult {
+        let item_type_nid = self.tree.unique_child(rec_nid);
+        let dat_node = self.tree.get_item(dat_nid);
+        if let NodeContent::DatEnum = dat_node.value.content {
+            let children = dat_node.children().clone();
+            match (dat_node.value.name.as_deref(), children.as_slice()) {
+                (Some("None"), []) => {
+                    self.io.write_all(&[0])?;
+                    return Ok(());
+                }
+                (Some("Some"), [tuple_nid]) => {
+                    let tuple = self.tree.get_item(*tuple_nid);
+                    if let (NodeContent::DatTuple, [value_nid]) =
+                        (&tuple.value.content, tuple.children().as_slice())
+                    {
+                        let value_nid = *value_nid;
+                        self.io.write_all(&[1])?;
+                        return self.write(item_type_nid, value_nid);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.error(CompilationError::ExpectedDatOption(dat_nid));
+        Ok(())
+    }
+
+    /// Decode a value written according to given recipe type, and return a key ordering values
+    /// the same way as the `Ord` implementation of the corresponding rust type.
+    ///
+    /// # Arguments
+    ///
+    /// * `rec_nid` - Recipe type node Id
+    /// * `bytes` - Binary representation of the value. Decoded bytes are consumed.
+    fn sort_key(&mut self, rec_nid: u32, bytes: &mut &[u8]) -> SortKey {
+        fn take<'b>(bytes: &mut &'b [u8], n: usize) -> &'b [u8] {
+            let (head, tail) = bytes.split_at(n);
+            *bytes = tail;
+            head
+        }
+        fn take_len(bytes: &mut &[u8]) -> usize {
+            let mut len = [0; 8];
+            len.copy_from_slice(take(bytes, 8));
+            u64::from_le_bytes(len) as usize
+        }
+        match self.tree.get(rec_nid).content.clone() {
+            NodeContent::RecInt { bit_size, signed } => {
+                // Integers of any width are stored in whole bytes
+                let int_bytes = take(bytes, bit_size.div_ceil(8) as usize);
+                let modulus = BigInt::from(1) << bit_size;
+                let mut value = BigInt::from_bytes_le(Sign::Plus, int_bytes) % &modulus;
+                if signed && value.bit(u64::from(bit_size - 1)) {
+                    value -= modulus;
+                }
+                SortKey::Int(value)
+            }
+            NodeContent::RecFloat { size } => {
+                let value = if size == 32 {
+                    let mut float = [0; 4];
+                    float.copy_from_slice(take(bytes, 4));
+                    f32::from_le_bytes(float) as f64
+                } else {
+                    let mut float = [0; 8];
+                    float.copy_from_slice(take(bytes, 8));
+                    f64::from_le_bytes(float)
+                };
+                // Same ordering as `f64::total_cmp`
+                let mut bits = value.to_bits() as i64;
+                bits ^= (((bits >> 63) as u64) >> 1) as i64;
+                SortKey::Int(BigInt::from(bits))
+            }
+            NodeContent::RecString => {
+                let len = take_len(bytes);
+                SortKey::Bytes(take(bytes, len).to_vec())
+            }
+            NodeContent::RecChar => {
+                // Length of the UTF-8 sequence is given by its first byte
+                let len = match bytes[0] {
+                    0x00..=0x7f => 1,
+                    0xc0..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    _ => 4,
+                };
+                let c = std::str::from_utf8(take(bytes, len)).unwrap();
+                SortKey::Int(BigInt::from(c.chars().next().unwrap() as u32))
+            }
+            NodeContent::RecList | NodeContent::RecSet => {
+                let item_type_nid = self.tree.unique_child(rec_nid);
+                let len = take_len(bytes);
+                SortKey::Seq(
+                    (0..len)
+                        .map(|_| self.sort_key(item_type_nid, bytes))
+                        .collect(),
+                )
+            }
+            NodeContent::RecMap { .. } => {
+                let generic_args = self.tree.children(rec_nid).clone();
+                let len = take_len(bytes);
+                SortKey::Seq(
+                    (0..len)
+                        .map(|_| {
+                            SortKey::Seq(vec![
+                                self.sort_key(generic_args[0], bytes),
+                                self.sort_key(generic_args[1], bytes),
+                            ])
+                        })
+                        .collect(),
+                )
+            }
+            NodeContent::RecOption => {
+                let item_type_nid = self.tree.unique_child(rec_nid);
+                let mut key = vec![SortKey::Int(BigInt::from(take(bytes, 1)[0]))];
+                if key[0] != SortKey::Int(BigInt::from(0)) {
+                    key.push(self.sort_key(item_type_nid, bytes));
+                }
+                SortKey::Seq(key)
+            }
+            NodeContent::RecBox => {
+                let item_type_nid = self.tree.unique_child(rec_nid);
+                self.sort_key(item_type_nid, bytes)
+            }
+            NodeContent::RecAlias => {
+                let type_nid = *self.tree.children(rec_nid).last().unwrap();
+                self.sort_key(type_nid, bytes)
+            }
+            NodeContent::RecArray { len } => {
+                let item_type_nid = self.tree.unique_child(rec_nid);
+                SortKey::Seq(
+                    (0..len)
+                        .map(|_| self.sort_key(item_type_nid, bytes))
+                        .collect(),
+                )
+            }
+            NodeContent::RecStruct | NodeContent::RecTuple => {
+                // Members are compared in declaration order. The type of members is their first
+                // child.
+                let members: Vec<u32> = self
+                    .tree
+                    .children(rec_nid)
+                    .iter()
+                    .copied()
+                    .filter(|&nid| {
+                        matches!(
+                            self.tree.get(nid).content,
+                            NodeContent::RecStructMember | NodeContent::RecTupleMember
+                        )
+                    })
+                    .collect();
+                SortKey::Seq(
+                    members
+                        .into_iter()
+                        .map(|nid| {
+                            let type_nid = self.tree.children(nid)[0];
+                            self.sort_key(type_nid, bytes)
+                        })
+                        .collect(),
+                )
+            }
+            NodeContent::RecEnum { key_type } => {
+                // Enumerations are ordered by value, then by data.
+                let value = match self.sort_key(key_type.unwrap_id(), bytes) {
+                    SortKey::Int(value) => value,
+                    _ => panic!(),
+                };
+                let item_nid = *self
+                    .tree
+                    .children(rec_nid)
+                    .iter()
+                    .find(|&&nid| {
+                        matches!(&self.tree.get(nid).content,
+                            NodeContent::RecEnumItem { value: v, .. } if *v == value)
+                    })
+                    .unwrap();
+                let mut key = vec![SortKey::Int(value)];
+                if let Some(data_type_nid) = self.tree.get_item(item_nid).unique_child_or_none() {
+                    key.push(self.sort_key(data_type_nid, bytes));
+                }
+                SortKey::Seq(key)
+            }
+            NodeContent::RecTypeInst { tid } => {
+                // Generic stack is handled the same way as in `write`
+                let may_be_generic = self.tree.number_of_generic_types(tid.unwrap_id()) > 0;
+                if may_be_generic {
+                    let generics = self.tree.children(rec_nid).clone();
+                    self.generic_stack.push(generics);
+                }
+                let key = self.sort_key(tid.unwrap_id(), bytes);
+                if may_be_generic {
+                    self.generic_stack.pop();
+                }
+                key
+            }
+            NodeContent::RecGeneric { index } => {
+                let current_generics = self.generic_stack.pop().unwrap();
+                let type_nid = current_generics[index as usize];
+                let key = self.sort_key(type_nid, bytes);
+                self.generic_stack.push(current_generics);
+                key
+            }
+            _ => panic!(),
         }
-        Ok(())
     }
 
-    /// Write given data node as given Map node
+    /// Return true if the given recipe type node designates an `Option`, following type
+    /// instantiations and generic arguments.
     ///
     /// # Arguments
     ///
-    /// * `rec_nid` - Id of the recipe structure node
-    /// * `dat_nid` - Id of the data node
-    fn write_map(&mut self, rec_nid: u32, dat_nid: u32) -> WriteResult {
-        let generic_args = self.tree.children(rec_nid).clone();
-        assert_eq!(generic_args.len(), 2);
-        let items = self.tree.children(dat_nid).clone();
-        let bytes = items.len().to_le_bytes();
-        self.io.write(&bytes)?;
-        for &item_nid in items.iter() {
-            let item_children = self.tree.children(item_nid).clone();
-            assert_eq!(item_children.len(), 2);
-            for i in 0..2 {
-                self.write(generic_args[i], item_children[i])?
+    /// * `rec_nid` - Recipe type node Id
+    fn is_option(&self, rec_nid: u32) -> bool {
+        let mut nid = rec_nid;
+        // Generic arguments are resolved the same way as in `write`, each generic argument being
+        // defined in the scope of the previous generic stack level.
+        let mut depth = self.generic_stack.len();
+        loop {
+            match &self.tree.get(nid).content {
+                NodeContent::RecOption => return true,
+                NodeContent::RecTypeInst { tid } => nid = tid.unwrap_id(),
+                NodeContent::RecAlias => nid = *self.tree.children(nid).last().unwrap(),
+                NodeContent::RecGeneric { index } => {
+                    depth -= 1;
+                    nid = self.generic_stack[depth][*index as usize];
+                }
+                _ => return false,
             }
         }
-        Ok(())
     }
 
     /// Write given data node as given struct recipe node
@@ -1488,12 +2847,14 @@ impl Compiler<'_> {
             for nid_child in dat_node_item.children().clone() {
                 let child = self.tree.get_item(nid_child);
                 if let NodeContent::DatMapAssignment = child.value.content {
-                    // We must check that there is no associated enumeration data, so we can be
+                    // The key must be an enumeration value without associated data, so we can be
                     // sure this is only an identifier.
                     let children = child.children();
                     assert_eq!(children.len(), 2);
                     let key = self.tree.get_item(children[0]);
-                    if key.children().len() != 0 {
+                    if !matches!(key.value.content, NodeContent::DatEnum)
+                        || !key.children().is_empty()
+                    {
                         self.error(CompilationError::ExpectedDatIdentifier(nid_child));
                         error = true;
                     }
@@ -1508,29 +2869,46 @@ impl Compiler<'_> {
                 return Ok(());
             }
 
+            if !self.options.allow_unknown_members {
+                self.check_unknown_members(rec_node, dat_node);
+            }
+
             // Iterate all members of the recipe structure
             for &child in self.tree.children(rec_node).clone().iter() {
                 match self.tree.get(child).content {
-                    NodeContent::RecStructMember { .. } => self.write(child, dat_node)?,
+                    NodeContent::RecStructMember => self.write(child, dat_node)?,
                     NodeContent::RecEnum { .. }
                     | NodeContent::RecGeneric { .. }
-                    | NodeContent::RecStruct { .. }
+                    | NodeContent::RecStruct
+                    | NodeContent::RecMod
+                    | NodeContent::RecImport { .. }
                     | NodeContent::RecTuple
+                    | NodeContent::RecArray { .. }
                     | NodeContent::RecInt { .. }
                     | NodeContent::RecFloat { .. }
+                    | NodeContent::RecString
+                    | NodeContent::RecChar
                     | NodeContent::RecList
-                    | NodeContent::RecMap
+                    | NodeContent::RecSet
+                    | NodeContent::RecMap { .. }
+                    | NodeContent::RecOption
+                    | NodeContent::RecBox
+                    | NodeContent::RecAlias
                     | NodeContent::RecTypeInst { .. } => {}
                     NodeContent::RecEnumItem { .. }
-                    | NodeContent::RecTupleMember { .. }
+                    | NodeContent::RecTupleMember
                     | NodeContent::DatMap
                     | NodeContent::DatMapAssignment
                     | NodeContent::DatTupleMember
                     | NodeContent::DatInt { .. }
                     | NodeContent::DatFloat { .. }
+                    | NodeContent::DatString { .. }
+                    | NodeContent::DatChar { .. }
+                    | NodeContent::DatBinary { .. }
                     | NodeContent::DatEnum
                     | NodeContent::DatTuple
-                    | NodeContent::DatList => {
+                    | NodeContent::DatList
+                    | NodeContent::DatSet => {
                         panic!()
                     }
                 }
@@ -1577,9 +2955,23 @@ impl Compiler<'_> {
                 self.write(rec_type_id, nid_dat)?;
                 // The member assignment must be unique. Look if there are some others and
                 // report errors.
-                while let Some(member) = iter.next() {
-                    self.error(CompilationError::RedefinedValue(*member));
+                for second in iter {
+                    self.error(CompilationError::RedefinedValue {
+                        first: *member,
+                        second: *second,
+                    });
+                }
+            } else if let Some(&default_nid) = self.tree.children(rec_nid).get(1) {
+                // Member is not defined in the data, use the default value from the recipe.
+                // The default value is checked against the member type only when it is used.
+                if let NodeContent::DatBinary { bytes } = &self.tree.get(default_nid).content {
+                    self.io.write_all(bytes)?;
+                } else {
+                    self.write(rec_type_id, default_nid)?;
                 }
+            } else if self.is_option(rec_type_id) {
+                // Optional members can be omitted, in which case they are None.
+                self.io.write_all(&[0])?;
             } else {
                 self.error(CompilationError::UndefinedValue(rec_nid));
             }
@@ -1596,6 +2988,10 @@ impl Compiler<'_> {
     /// * `rec_node` - Id of the recipe structure node
     /// * `dat_node` - Id of the data node
     fn write_tuple(&mut self, rec_node: u32, dat_node: u32) -> WriteResult {
+        if !matches!(self.tree.get(dat_node).content, NodeContent::DatTuple) {
+            self.error(CompilationError::ExpectedDatTuple(dat_node));
+            return Ok(());
+        }
         let tuple_children = self.tree.children(rec_node).clone();
         let data_children = self.tree.children(dat_node).clone();
         if tuple_children.len() != data_children.len() {
@@ -1605,13 +3001,11 @@ impl Compiler<'_> {
             });
             Ok(())
         } else {
-            // All children of `NodeContent::RecTuple` are `NodeContent::RecTupleMember`.
+            // All children of `NodeContent::RecTuple` are `NodeContent::RecTupleMember`, whose
+            // only child is the member type.
             for (t, d) in tuple_children.iter().zip(data_children.iter()) {
-                if let NodeContent::RecTupleMember { tid } = &self.tree.get(*t).content.clone() {
-                    self.write(tid.unwrap_id(), *d)?;
-                } else {
-                    panic!();
-                }
+                let type_nid = self.tree.children(*t)[0];
+                self.write(type_nid, *d)?;
             }
             Ok(())
         }
@@ -1631,19 +3025,23 @@ impl Compiler<'_> {
         let dat_node = self.tree.get(dat_nid);
         if let NodeContent::DatEnum = dat_node.content {
             // Find the value corresponding to the name in the enumeration
+            // Generic types are children of the enumeration too, and must be skipped.
             match self.tree.children(rec_nid).iter().find(|&&a| {
-                self.tree.get(a).name.clone().unwrap() == dat_node.name.clone().unwrap()
+                let node = self.tree.get(a);
+                matches!(node.content, NodeContent::RecEnumItem { .. })
+                    && node.name.clone().unwrap() == dat_node.name.clone().unwrap()
             }) {
                 Some(nid) => {
                     if let NodeContent::RecInt { bit_size, signed } =
                         self.tree.get(rec_type_id).content
                     {
-                        if let NodeContent::RecEnumItem { value } = &self.tree.get(*nid).content {
+                        if let NodeContent::RecEnumItem { value, .. } = &self.tree.get(*nid).content
+                        {
                             // Bounds are checked but we ignore the result as it MUST pass here.
                             // Enumeration value correctness should be done in a previous
                             // compilation phase. Checking here would produce an error message each
                             // time the enumeration is used, which is not good.
-                            match write_int_check_bounds(&mut self.io, bit_size, signed, &value) {
+                            match write_int_check_bounds(&mut self.io, bit_size, signed, value) {
                                 Ok(()) => {}
                                 Err(WriteIntCheckBoundsError::OutOfBounds) => panic!(), // Cannot happen
                                 Err(WriteIntCheckBoundsError::IOError(e)) => return Err(e),
@@ -1680,119 +3078,403 @@ impl Compiler<'_> {
                 }
             }
         } else {
-            panic!();
+            self.error(CompilationError::ExpectedDatIdentifier(dat_nid));
+            Ok(())
         }
     }
 }
 
-fn print_errors(tree: &NodeTree, errors: &Vec<CompilationError>) {
-    for error in errors {
-        match error {
-            CompilationError::DataNotStruct(node) => {
-                println!("Error: data {} must be a structure", tree.node_path(*node));
-            }
-            CompilationError::EnumTypeIsNotInt(node) => {
-                println!(
-                    "Error: enumeration type {} is not an integer",
-                    tree.node_path(*node)
-                );
-            }
-            CompilationError::EnumValueOutOfBounds(node) => {
-                println!(
-                    "Error: enumeration value {} out of bounds",
+impl CompilationError {
+    /// Build the diagnostic reported for the error.
+    ///
+    /// Error codes are stable: new errors get new codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `tree` - Node tree the error refers to
+    fn diagnostic(&self, tree: &NodeTree) -> Diagnostic {
+        let location = |nid: &u32| tree.get(*nid).source.clone();
+        match self {
+            CompilationError::AliasCycle(node) => Diagnostic::error(
+                "E0028",
+                format!("type alias {} refers to itself", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ArraySizeMismatch {
+                node_array,
+                node_data,
+                expected,
+            } => Diagnostic::error(
+                "E0001",
+                format!(
+                    "incorrect number of elements in {} for array {}, expected {}",
+                    tree.node_path(*node_data),
+                    tree.node_path(*node_array),
+                    expected
+                ),
+                location(node_data),
+            )
+            .with_related(location(node_array), "array declared here"),
+            CompilationError::DataNotStruct(node) => Diagnostic::error(
+                "E0002",
+                format!("data {} must be a structure", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::DuplicateMapKey { first, second } => Diagnostic::error(
+                "E0025",
+                format!("duplicate key in map {}", tree.node_path(*second)),
+                location(second),
+            )
+            .with_related(location(first), "first used here"),
+            CompilationError::DuplicateSetItem { first, second } => Diagnostic::error(
+                "E0029",
+                format!("duplicate item in set {}", tree.node_path(*second)),
+                location(second),
+            )
+            .with_related(location(first), "first used here"),
+            CompilationError::EnumTypeIsNotInt(node) => Diagnostic::error(
+                "E0003",
+                format!(
+                    "enumeration type {} is not an integer",
                     tree.node_path(*node)
-                );
-            }
+                ),
+                location(node),
+            ),
+            CompilationError::EnumValueOutOfBounds(node) => Diagnostic::error(
+                "E0004",
+                format!("enumeration value {} out of bounds", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::EnumDuplicateValue { first, second } => Diagnostic::error(
+                "E0005",
+                format!(
+                    "enumeration value {} has the same value as {}",
+                    tree.node_path(*second),
+                    tree.node_path(*first)
+                ),
+                location(second),
+            )
+            .with_related(location(first), "first value declared here"),
             CompilationError::EnumUndefinedName {
                 node_enum,
                 node_name,
-            } => {
-                println!(
-                    "Error: invalid name in {} for enumeration {}",
-                    tree.node_path(*node_enum),
-                    tree.node_path(*node_name)
-                );
-            }
-            CompilationError::EnumUndefinedData { data_nid } => {
-                println!(
-                    "Error: enumeration data not defined for {}",
+            } => Diagnostic::error(
+                "E0006",
+                format!(
+                    "invalid name in {} for enumeration {}",
+                    tree.node_path(*node_name),
+                    tree.node_path(*node_enum)
+                ),
+                location(node_name),
+            )
+            .with_related(location(node_enum), "enumeration declared here"),
+            CompilationError::EnumUndefinedData { data_nid } => Diagnostic::error(
+                "E0007",
+                format!(
+                    "enumeration data not defined for {}",
                     tree.node_path(*data_nid)
-                );
-            }
-            CompilationError::ExpectedDatFloat(node) => {
-                println!("Error: expected float for {}", tree.node_path(*node));
-            }
-            CompilationError::ExpectedDatInt(node) => {
-                println!("Error: expected integer for {}", tree.node_path(*node));
-            }
-            CompilationError::ExpectedDatStruct(node) => {
-                println!(
-                    "Error: expected structure for {}={}",
-                    *node,
-                    tree.node_path(*node)
-                );
-            }
-            CompilationError::ExpectedDatIdentifier(nid) => {
-                println!(
-                    "Error: expected identifier for structure assignement at {}",
-                    tree.node_path(*nid)
-                );
-            }
+                ),
+                location(data_nid),
+            ),
+            CompilationError::ExpectedDatFloat(node) => Diagnostic::error(
+                "E0008",
+                format!("expected float for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatInt(node) => Diagnostic::error(
+                "E0009",
+                format!("expected integer for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatList(node) => Diagnostic::error(
+                "E0010",
+                format!("expected list for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatMap(node) => Diagnostic::error(
+                "E0026",
+                format!("expected map for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatOption(node) => Diagnostic::error(
+                "E0011",
+                format!("expected None or Some(value) for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatSet(node) => Diagnostic::error(
+                "E0030",
+                format!("expected set for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatString(node) => Diagnostic::error(
+                "E0012",
+                format!("expected string for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatChar(node) => Diagnostic::error(
+                "E0027",
+                format!("expected character for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatStruct(node) => Diagnostic::error(
+                "E0013",
+                format!("expected structure for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatTuple(node) => Diagnostic::error(
+                "E0032",
+                format!("expected tuple for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ExpectedDatIdentifier(node) => Diagnostic::error(
+                "E0014",
+                format!("expected identifier for {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::ImportCycle { path, node } => Diagnostic::error(
+                "E0015",
+                format!("import of \"{}\" is cyclic", path),
+                location(node),
+            ),
+            CompilationError::ImportNotFound { path, node } => Diagnostic::error(
+                "E0016",
+                format!("imported file \"{}\" not found", path),
+                location(node),
+            ),
             CompilationError::GenericArgCountMismatch {
                 nid,
                 expected,
                 current,
-            } => {
-                println!(
-                    "Error: invalid generic type argument count for {}, expected {}, got {}",
+            } => Diagnostic::error(
+                "E0017",
+                format!(
+                    "invalid generic type argument count for {}, expected {}, got {}",
                     tree.node_path(*nid),
                     expected,
                     current
-                );
-            }
-            CompilationError::IOError(e) => {
-                println!("Error: {}", e);
-            }
-            CompilationError::RedefinedValue(node) => {
-                println!("Error: {} already defined", tree.node_path(*node));
-            }
+                ),
+                location(nid),
+            ),
+            CompilationError::InvalidEscapeSequence(node) => Diagnostic::error(
+                "E0018",
+                format!(
+                    "invalid escape sequence in string {}",
+                    tree.node_path(*node)
+                ),
+                location(node),
+            ),
+            CompilationError::InvalidFloat(node) => Diagnostic::error(
+                "E0031",
+                format!("invalid floating point number {}", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::RedefinedValue { first, second } => Diagnostic::error(
+                "E0019",
+                format!("{} already defined", tree.node_path(*second)),
+                location(second),
+            )
+            .with_related(location(first), "first defined here"),
             CompilationError::TupleSizeMismatch {
                 node_tuple,
                 node_data,
-            } => {
-                println!(
-                    "Error: incorrect number of elements in {} for tuple {}",
+            } => Diagnostic::error(
+                "E0020",
+                format!(
+                    "incorrect number of elements in {} for tuple {}",
                     tree.node_path(*node_data),
                     tree.node_path(*node_tuple)
+                ),
+                location(node_data),
+            )
+            .with_related(location(node_tuple), "tuple declared here"),
+            CompilationError::UndefinedValue(node) => Diagnostic::error(
+                "E0021",
+                format!("{} is undefined", tree.node_path(*node)),
+                location(node),
+            ),
+            CompilationError::UnknownMember {
+                node,
+                node_struct,
+                suggestions,
+            } => {
+                let mut message = format!(
+                    "unknown member \"{}\" for structure {}",
+                    tree.get(*node).name_or_anonymous(),
+                    tree.node_path(*node_struct)
                 );
+                if let Some((last, first)) = suggestions.split_last() {
+                    message += ", did you mean ";
+                    for suggestion in first {
+                        message += &format!("\"{}\", ", suggestion);
+                    }
+                    if !first.is_empty() {
+                        message.truncate(message.len() - 2);
+                        message += " or ";
+                    }
+                    message += &format!("\"{}\"?", last);
+                }
+                Diagnostic::error("E0024", message, location(node))
+                    .with_related(location(node_struct), "structure declared here")
             }
-            CompilationError::UndefinedValue(node) => {
-                println!("Error: {} is undefined", tree.node_path(*node));
-            }
-            CompilationError::UnresolvedType { path, node } => {
-                println!(
-                    "Error: unresolved typename \"{}\" for {}",
+            CompilationError::UnresolvedType { path, node } => Diagnostic::error(
+                "E0022",
+                format!(
+                    "unresolved typename \"{}\" for {}",
                     path,
                     tree.node_path(*node)
-                );
+                ),
+                location(node),
+            ),
+            CompilationError::ValueOutOfBounds(node) => Diagnostic::error(
+                "E0023",
+                format!("value {} out of bounds", tree.node_path(*node)),
+                location(node),
+            ),
+        }
+    }
+}
+
+/// Syntax error in a recipe or in data
+#[derive(Debug)]
+pub struct ParseError {
+    path: Option<PathBuf>,
+    line: usize,
+    column: usize,
+    expected: Vec<String>,
+    snippet: String,
+}
+
+impl ParseError {
+    /// Create a parse error from a pest error.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Source which failed to be parsed
+    /// * `error` - Error returned by pest
+    fn new(source: &Source, error: pest::error::Error<Rule>) -> Self {
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+        };
+        let mut expected = Vec::new();
+        if let ErrorVariant::ParsingError { positives, .. } = &error.variant {
+            for &rule in positives {
+                let description = describe_rule(rule).to_string();
+                if !expected.contains(&description) {
+                    expected.push(description);
+                }
             }
-            CompilationError::ValueOutOfBounds(node) => {
-                println!("Error: value {} out of bounds", tree.node_path(*node));
+        }
+        ParseError {
+            path: source.path.clone(),
+            line,
+            column,
+            expected,
+            snippet: source
+                .code
+                .lines()
+                .nth(line - 1)
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    /// Path of the file where the error is, None if the source code does not come from a file
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Line of the error, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Column of the error, in characters, starting at 1
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Descriptions of the tokens which were expected at the error position
+    pub fn expected(&self) -> &[String] {
+        &self.expected
+    }
+
+    /// Source code line where the error is
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:", path.display())?,
+            None => write!(f, "<string>:")?,
+        }
+        write!(f, "{}:{}: syntax error", self.line, self.column)?;
+        if let Some((last, first)) = self.expected.split_last() {
+            if first.is_empty() {
+                write!(f, ", expected {}", last)?;
+            } else {
+                write!(f, ", expected {} or {}", first.join(", "), last)?;
             }
         }
+        // Caret under the error position. Tabs are kept so the caret stays aligned.
+        let padding: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(f, "\n  | {}\n  | {}^", self.snippet, padding)
+    }
+}
+
+/// Human readable description of a grammar rule, used in parse errors.
+///
+/// # Arguments
+///
+/// * `rule` - Grammar rule
+fn describe_rule(rule: Rule) -> &'static str {
+    match rule {
+        Rule::EOI => "end of input",
+        Rule::identifier | Rule::dat_enum => "identifier",
+        Rule::rec_path | Rule::rec_type_inst => "type name",
+        Rule::uint | Rule::int => "integer",
+        Rule::float => "float",
+        Rule::string => "string",
+        Rule::character => "character",
+        Rule::member => "member",
+        Rule::rec_struct | Rule::rec_struct_anonymous => "struct",
+        Rule::rec_enum | Rule::rec_enum_anonymous => "enum",
+        Rule::rec_enum_value | Rule::rec_enum_values => "enum variant",
+        Rule::rec_enum_storage => "enum storage type",
+        Rule::rec_alias => "type alias",
+        Rule::rec_enum_discriminant => "discriminant",
+        Rule::rec_tuple | Rule::dat_tuple => "tuple",
+        Rule::rec_array => "array",
+        Rule::rec_mod => "mod",
+        Rule::rec_import => "import",
+        Rule::rec_generic_decl => "generic parameters",
+        Rule::rec_generic_inst => "generic arguments",
+        Rule::dat_map => "map",
+        Rule::dat_list => "list",
+        Rule::dat_set => "set",
+        Rule::dat_assignment | Rule::dat => "assignment",
+        _ => "declaration",
     }
 }
 
 #[derive(Debug)]
 pub enum LoadError {
-    CompilationErrors,
+    CompilationErrors(Vec<Diagnostic>),
     InvalidRecExtension,
     InvalidDatExtension,
     RecFileAccess,
     DatFileAccess,
     IOError(std::io::Error),
-    RecipeParseError,
-    DataParseError,
+    RecipeParseError(ParseError),
+    DataParseError(ParseError),
+    DeserializeError(bincode::Error),
 }
 
 impl From<std::io::Error> for LoadError {
@@ -1801,8 +3483,28 @@ impl From<std::io::Error> for LoadError {
     }
 }
 
+impl From<bincode::Error> for LoadError {
+    fn from(e: bincode::Error) -> Self {
+        LoadError::DeserializeError(e)
+    }
+}
+
+/// Options for the compilation of data files.
+#[derive(Debug, Default, Clone)]
+pub struct LoadOptions {
+    /// Directories in which imported recipe files are searched, after the directory of the
+    /// importing file.
+    pub search_paths: Vec<PathBuf>,
+    /// Ignore members defined in the data but not declared in the recipe, instead of reporting
+    /// them as errors. Useful for files which must be readable by older versions of a program.
+    pub allow_unknown_members: bool,
+    /// Sort the entries of all maps by key value, so that identical data always produces
+    /// identical binary files. Maps built from `BTreeMap` and sets are always sorted.
+    pub sort_maps: bool,
+}
+
 /// Checks if a binary file needs to be compiled, by looking if the binary file exists and if its
-/// modification date is older than the recipe and data files.
+/// modification date is older than the recipe, data and imported recipe files.
 ///
 /// # Arguments
 ///
@@ -1810,29 +3512,37 @@ impl From<std::io::Error> for LoadError {
 /// * `dat_path` - Path to the data file
 /// * `bin_path` - Path to the binary file
 fn is_compilation_required(
-    rec_path: Option<&str>,
-    dat_path: &str,
-    bin_path: &str,
+    rec_path: Option<&Path>,
+    dat_path: &Path,
+    bin_path: &Path,
 ) -> Result<bool, LoadError> {
-    match std::fs::metadata(bin_path) {
-        Ok(bin_metadata) => match std::fs::metadata(dat_path) {
-            Ok(dat_metadata) => {
-                let bin_modified = bin_metadata.modified().unwrap();
-                if let Some(rec_path) = rec_path {
-                    match std::fs::metadata(rec_path) {
-                        Ok(rec_metadata) => Ok((bin_modified > dat_metadata.modified().unwrap())
-                            || (bin_modified > rec_metadata.modified().unwrap())),
-                        Err(_) => Err(LoadError::RecFileAccess),
-                    }
-                } else {
-                    Ok(bin_modified > dat_metadata.modified().unwrap())
-                }
-            }
-            Err(_) => Err(LoadError::DatFileAccess),
-        },
+    let bin_modified = match std::fs::metadata(bin_path) {
+        Ok(bin_metadata) => bin_metadata.modified()?,
         // binary file does not exist, we must compile
-        Err(_) => Ok(true),
+        Err(_) => return Ok(true),
+    };
+    let dat_metadata = std::fs::metadata(dat_path).map_err(|_| LoadError::DatFileAccess)?;
+    if dat_metadata.modified()? >= bin_modified {
+        return Ok(true);
+    }
+    if let Some(rec_path) = rec_path {
+        let rec_metadata = std::fs::metadata(rec_path).map_err(|_| LoadError::RecFileAccess)?;
+        if rec_metadata.modified()? >= bin_modified {
+            return Ok(true);
+        }
+        // Imported recipe files are listed next to the binary file when it is compiled. A missing
+        // list or a missing imported file means the binary may be outdated.
+        let Ok(imports) = std::fs::read_to_string(bin_path.with_extension("deps")) else {
+            return Ok(true);
+        };
+        for import in imports.lines() {
+            match std::fs::metadata(import) {
+                Ok(metadata) if metadata.modified()? < bin_modified => {}
+                _ => return Ok(true),
+            }
+        }
     }
+    Ok(false)
 }
 
 /// Write the binary representation of string data to be compiled, with the recipe given as a
@@ -1843,26 +3553,73 @@ fn is_compilation_required(
 /// * `dest` - A writable stream
 /// * `rec` - Recipe string
 /// * `dat` - Data string
-pub fn write_from_string_with_recipe<'a>(
-    out: &'a mut dyn std::io::Write,
+pub fn write_from_string_with_recipe(
+    out: &mut dyn std::io::Write,
     rec: &str,
     dat: &str,
 ) -> Result<(), LoadError> {
+    write_from_string_with_recipe_and_options(out, rec, dat, &LoadOptions::default())
+}
+
+/// Write the binary representation of string data to be compiled, with the recipe given as a
+/// string and compilation options.
+///
+/// As the recipe does not come from a file, imported recipe files are only searched in the search
+/// paths of the options.
+///
+/// # Arguments
+///
+/// * `dest` - A writable stream
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+/// * `options` - Compilation options
+pub fn write_from_string_with_recipe_and_options(
+    out: &mut dyn std::io::Write,
+    rec: &str,
+    dat: &str,
+    options: &LoadOptions,
+) -> Result<(), LoadError> {
+    write_with_recipe(out, Source::new(None, rec), Source::new(None, dat), options)?;
+    Ok(())
+}
+
+/// Write the binary representation of data to be compiled with a recipe, and return the paths of
+/// the imported recipe files.
+///
+/// # Arguments
+///
+/// * `dest` - A writable stream
+/// * `rec` - Recipe source
+/// * `dat` - Data source
+/// * `options` - Compilation options
+fn write_with_recipe(
+    out: &mut dyn std::io::Write,
+    rec: Rc<Source>,
+    dat: Rc<Source>,
+    options: &LoadOptions,
+) -> Result<Vec<PathBuf>, LoadError> {
     let mut compiler = Compiler::new(out);
+    compiler.options = options.clone();
     let node_root = compiler.tree.create_root_struct();
     compiler.tree.populate_natives(node_root);
-    let node_rec = compiler.tree.parse_recipe_string(rec).unwrap();
+    let mut import_stack = Vec::new();
+    if let Some(path) = &rec.path {
+        import_stack.push(path.canonicalize()?);
+    }
+    let node_rec = compiler.tree.parse_recipe_source(rec)?;
     compiler.tree.child(node_root, node_rec);
+    compiler.resolve_imports(node_rec, &mut import_stack)?;
+    compiler.check_errors()?;
     compiler.resolve_types(node_rec);
-    if compiler.errors.len() > 0 {
-        return Err(LoadError::CompilationErrors);
-    }
+    compiler.check_alias_cycles(node_rec);
+    compiler.check_errors()?;
     let node_dat = match compiler.tree.get(node_rec).content {
-        NodeContent::RecStruct => compiler.tree.parse_dat_map_string(dat).unwrap(),
-        _ => compiler.tree.parse_dat_value_string(dat).unwrap(),
+        NodeContent::RecStruct => compiler.tree.parse_dat_map_source(dat)?,
+        _ => compiler.tree.parse_dat_value_source(dat)?,
     };
     compiler.write(node_rec, node_dat)?;
-    Ok(())
+    compiler.check_errors()?;
+    Ok(compiler.imports)
 }
 
 /// Load an object from a data file, with recipe built using [`Recipe`] trait.
@@ -1878,6 +3635,22 @@ pub fn write_from_string_with_recipe<'a>(
 ///
 /// * `dat_path` - Path to the data file. File extension must be `.dat`.
 pub fn load_from_file<T>(path: &str) -> Result<T, LoadError>
+where
+    T: DeserializeOwned + Recipe,
+{
+    load_from_file_with_options(path, &LoadOptions::default())
+}
+
+/// Load an object from a data file, with recipe built using [`Recipe`] trait and compilation
+/// options.
+///
+/// See [`load_from_file`] for details about caching.
+///
+/// # Arguments
+///
+/// * `dat_path` - Path to the data file. File extension must be `.dat`.
+/// * `options` - Compilation options
+pub fn load_from_file_with_options<T>(path: &str, options: &LoadOptions) -> Result<T, LoadError>
 where
     T: DeserializeOwned + Recipe,
 {
@@ -1887,20 +3660,26 @@ where
         return Err(LoadError::InvalidDatExtension);
     }
     let path_bin = path.with_extension("bin");
-    if is_compilation_required(None, path.to_str().unwrap(), path_bin.to_str().unwrap())? {
+    if is_compilation_required(None, path, &path_bin)? {
         let mut dat = String::new();
-        File::open(path).unwrap().read_to_string(&mut dat).unwrap();
-        let mut file = File::create(path_bin.clone()).unwrap();
-        let mut compiler = Compiler::new(&mut file);
+        File::open(path)?.read_to_string(&mut dat)?;
+        // Build in memory, so that the binary file is only created if compilation succeeds
+        let mut bin = Vec::<u8>::new();
+        let mut compiler = Compiler::new(&mut bin);
+        compiler.options = options.clone();
         let nid_rec = T::recipe(&mut compiler.tree);
-        let nid_dat = compiler.tree.parse_dat_map_string(dat.as_str()).unwrap();
+        let nid_dat = compiler
+            .tree
+            .parse_dat_map_source(Source::new(Some(path), &dat))?;
         compiler.resolve_types(nid_rec);
-        compiler.write(nid_rec, nid_dat).unwrap();
-        // TODO: in this case, we should build in RAM, save to file and deserialize from RAM, that
-        // should be faster.
+        compiler.check_errors()?;
+        compiler.write(nid_rec, nid_dat)?;
+        compiler.check_errors()?;
+        std::fs::write(path_bin, &bin)?;
+        return Ok(bincode::deserialize_from(&bin[..])?);
     }
-    let file = File::open(path_bin).unwrap();
-    return Ok(bincode::deserialize_from(file).unwrap());
+    let file = File::open(path_bin)?;
+    Ok(bincode::deserialize_from(file)?)
 }
 
 /// Load data from a string, with recipe built using [`Recipe`] trait.
@@ -1937,19 +3716,92 @@ where
 /// assert_eq!(values, vec![1, 2, 3]);
 /// ```
 pub fn load_from_string<T>(dat: &str) -> Result<T, LoadError>
+where
+    T: Recipe + DeserializeOwned,
+{
+    load_from_string_with_options(dat, &LoadOptions::default())
+}
+
+/// Load data from a string, with recipe built using [`Recipe`] trait and compilation options.
+///
+/// # Arguments
+///
+/// * `dat` - Data string
+/// * `options` - Compilation options
+///
+/// # Example
+///
+/// This example shows how to ignore members which are not part of the structure:
+/// ```
+/// use bakery::{load_from_string_with_options, LoadOptions};
+/// use bakery_derive::Recipe;
+/// use serde::Deserialize;
+///
+/// #[derive(Recipe, Deserialize, Debug, PartialEq)]
+/// struct GameConfig {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let options = LoadOptions {
+///     allow_unknown_members: true,
+///     ..Default::default()
+/// };
+/// let config: GameConfig =
+///     load_from_string_with_options("width: 1024, height: 768, vsync: true", &options).unwrap();
+/// assert_eq!(config, GameConfig { width: 1024, height: 768 });
+/// ```
+pub fn load_from_string_with_options<T>(dat: &str, options: &LoadOptions) -> Result<T, LoadError>
 where
     T: Recipe + DeserializeOwned,
 {
     let mut bin = Vec::<u8>::new();
-    let mut compiler = Compiler::new(&mut bin);
+    write_from_string_with_options::<T>(&mut bin, dat, options)?;
+    Ok(bincode::deserialize_from(&bin[..])?)
+}
+
+/// Write the binary representation of string data to be compiled, with recipe built using
+/// [`Recipe`] trait.
+///
+/// # Arguments
+///
+/// * `dest` - A writable stream
+/// * `dat` - Data string
+pub fn write_from_string<T>(out: &mut dyn std::io::Write, dat: &str) -> Result<(), LoadError>
+where
+    T: Recipe,
+{
+    write_from_string_with_options::<T>(out, dat, &LoadOptions::default())
+}
+
+/// Write the binary representation of string data to be compiled, with recipe built using
+/// [`Recipe`] trait and compilation options.
+///
+/// # Arguments
+///
+/// * `dest` - A writable stream
+/// * `dat` - Data string
+/// * `options` - Compilation options
+pub fn write_from_string_with_options<T>(
+    out: &mut dyn std::io::Write,
+    dat: &str,
+    options: &LoadOptions,
+) -> Result<(), LoadError>
+where
+    T: Recipe,
+{
+    let mut compiler = Compiler::new(out);
+    compiler.options = options.clone();
     let nid_rec = T::recipe(&mut compiler.tree);
     let nid_dat = match compiler.tree.get(nid_rec).content {
         NodeContent::RecStruct => compiler.tree.parse_dat_map_string(dat)?,
         _ => compiler.tree.parse_dat_value_string(dat)?,
     };
     compiler.resolve_types(nid_rec);
-    compiler.write(nid_rec, nid_dat).unwrap();
-    Ok(bincode::deserialize_from(&bin[..]).unwrap())
+    compiler.check_errors()?;
+    compiler.write(nid_rec, nid_dat)?;
+    compiler.check_errors()?;
+    Ok(())
 }
 
 /// Load an object from a data file, given a recipe defined in a recipe file.
@@ -1962,6 +3814,30 @@ where
 /// * `rec_path` - Path to the recipe file
 /// * `dat_path` - Path to the data file
 pub fn load_from_file_with_recipe<T>(rec_path: &str, dat_path: &str) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+{
+    load_from_file_with_recipe_and_options(rec_path, dat_path, &LoadOptions::default())
+}
+
+/// Load an object from a data file, given a recipe defined in a recipe file and compilation
+/// options.
+///
+/// If the binary image of the data file does not exist or is outdated, it is built and cached.
+/// If the recipe file or one of the recipe files it imports has been changed, the binary is
+/// rebuilt as well. Paths of the imported recipe files are saved next to the binary file, with the
+/// `.deps` extension.
+///
+/// # Arguments
+///
+/// * `rec_path` - Path to the recipe file
+/// * `dat_path` - Path to the data file
+/// * `options` - Compilation options
+pub fn load_from_file_with_recipe_and_options<T>(
+    rec_path: &str,
+    dat_path: &str,
+    options: &LoadOptions,
+) -> Result<T, LoadError>
 where
     T: DeserializeOwned,
 {
@@ -1977,32 +3853,30 @@ where
     let bin_path = dat_path.with_extension("bin");
 
     // If binary file is missing or if it is older than the data or recipe files, rebuild it.
-    match is_compilation_required(
-        Some(rec_path.to_str().unwrap()),
-        dat_path.to_str().unwrap(),
-        bin_path.to_str().unwrap(),
-    ) {
-        Ok(true) => {
-            let mut rec = String::new();
-            File::open(rec_path)
-                .unwrap()
-                .read_to_string(&mut rec)
-                .unwrap();
-            let mut dat = String::new();
-            File::open(dat_path)
-                .unwrap()
-                .read_to_string(&mut dat)
-                .unwrap();
-
-            let mut file = File::create(bin_path.clone()).unwrap();
-            write_from_string_with_recipe(&mut file, &rec, &dat)?;
-        }
-        Ok(false) => {}
-        Err(e) => {
-            return Err(e);
+    if is_compilation_required(Some(rec_path), dat_path, &bin_path)? {
+        let mut rec = String::new();
+        File::open(rec_path)?.read_to_string(&mut rec)?;
+        let mut dat = String::new();
+        File::open(dat_path)?.read_to_string(&mut dat)?;
+
+        // Build in memory, so that the binary file is only created if compilation succeeds
+        let mut bin = Vec::<u8>::new();
+        let imports = write_with_recipe(
+            &mut bin,
+            Source::new(Some(rec_path), &rec),
+            Source::new(Some(dat_path), &dat),
+            options,
+        )?;
+        let mut deps = String::new();
+        for import in imports {
+            deps.push_str(&import.to_string_lossy());
+            deps.push('\n');
         }
+        std::fs::write(bin_path.with_extension("deps"), deps)?;
+        std::fs::write(bin_path, &bin)?;
+        return Ok(bincode::deserialize_from(&bin[..])?);
     }
 
-    let file = File::open(bin_path).unwrap();
-    Ok(bincode::deserialize_from(file).unwrap())
+    let file = File::open(bin_path)?;
+    Ok(bincode::deserialize_from(file)?)
 }
@@ -1,4 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{Node, NodeContent, NodeTree, RecTypeId};
 use num_bigint::BigInt;
@@ -109,6 +114,30 @@ impl Recipe for i64 {
     }
 }
 
+impl Recipe for u128 {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        tree.create(Node::new_builtin(
+            "u128",
+            NodeContent::RecInt {
+                signed: false,
+                bit_size: 128,
+            },
+        ))
+    }
+}
+
+impl Recipe for i128 {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        tree.create(Node::new_builtin(
+            "i128",
+            NodeContent::RecInt {
+                signed: true,
+                bit_size: 128,
+            },
+        ))
+    }
+}
+
 impl Recipe for f32 {
     fn recipe(tree: &mut NodeTree) -> u32 {
         tree.create(Node::new_builtin("f32", NodeContent::RecFloat { size: 32 }))
@@ -121,6 +150,43 @@ impl Recipe for f64 {
     }
 }
 
+impl Recipe for String {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        tree.create(Node::new_builtin("String", NodeContent::RecString))
+    }
+}
+
+impl Recipe for char {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        tree.create(Node::new_builtin("char", NodeContent::RecChar))
+    }
+}
+
+// Paths are serialized as strings.
+impl Recipe for PathBuf {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        String::recipe(tree)
+    }
+}
+
+impl Recipe for () {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        tree.create(Node::new_anonymous(NodeContent::RecTuple))
+    }
+}
+
+// Durations are serialized as a structure with whole seconds and nanoseconds.
+impl Recipe for Duration {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        let node_secs = u64::recipe(tree);
+        let node_nanos = u32::recipe(tree);
+        let nid = tree.create_struct(None, "Duration");
+        tree.create_struct_member(nid, "secs", node_secs);
+        tree.create_struct_member(nid, "nanos", node_nanos);
+        nid
+    }
+}
+
 impl Recipe for bool {
     fn recipe(tree: &mut NodeTree) -> u32 {
         let node_u8 = u8::recipe(tree);
@@ -138,6 +204,7 @@ impl Recipe for bool {
                 "false",
                 NodeContent::RecEnumItem {
                     value: BigInt::from(0),
+                    explicit: true,
                 },
             ),
         );
@@ -148,6 +215,7 @@ impl Recipe for bool {
                 "true",
                 NodeContent::RecEnumItem {
                     value: BigInt::from(1),
+                    explicit: true,
                 },
             ),
         );
@@ -165,14 +233,91 @@ impl<T: Recipe> Recipe for Vec<T> {
     }
 }
 
-impl<K: Recipe, T: Recipe> Recipe for HashMap<K, T> {
+// Double-ended queues are serialized as lists.
+impl<T: Recipe> Recipe for VecDeque<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        Vec::<T>::recipe(tree)
+    }
+}
+
+impl<T: Recipe, S: BuildHasher> Recipe for HashSet<T, S> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        let t = T::recipe(tree);
+        let nid = tree.create(Node::new_anonymous(NodeContent::RecSet));
+        tree.child(nid, t);
+        nid
+    }
+}
+
+impl<T: Recipe> Recipe for BTreeSet<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        HashSet::<T>::recipe(tree)
+    }
+}
+
+impl<T: Recipe, const N: usize> Recipe for [T; N] {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        let t = T::recipe(tree);
+        let nid = tree.create(Node::new_anonymous(NodeContent::RecArray { len: N }));
+        tree.child(nid, t);
+        nid
+    }
+}
+
+// Boxes are serialized as their content.
+impl<T: Recipe> Recipe for Box<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        T::recipe(tree)
+    }
+}
+
+// Reference counted pointers are serialized as their content.
+impl<T: Recipe> Recipe for Rc<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        T::recipe(tree)
+    }
+}
+
+impl<T: Recipe> Recipe for Arc<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        T::recipe(tree)
+    }
+}
+
+impl<T: Recipe> Recipe for Option<T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        let t = T::recipe(tree);
+        let nid = tree.create(Node::new_anonymous(NodeContent::RecOption));
+        tree.child(nid, t);
+        nid
+    }
+}
+
+/// Create a map node with given key and value types, and return created node Id.
+///
+/// # Arguments
+///
+/// * `tree` - Current compiler node tree
+/// * `sorted` - Whether entries are sorted by key
+fn map_recipe<K: Recipe, T: Recipe>(tree: &mut NodeTree, sorted: bool) -> u32 {
+    let node_k = K::recipe(tree);
+    let node_t = T::recipe(tree);
+    let node = tree.create(Node::new_anonymous(NodeContent::RecMap { sorted }));
+    tree.child(node, node_k);
+    tree.child(node, node_t);
+    node
+}
+
+impl<K: Recipe, T: Recipe, S: BuildHasher> Recipe for HashMap<K, T, S> {
     fn recipe(tree: &mut NodeTree) -> u32 {
-        let node_k = K::recipe(tree);
-        let node_t = T::recipe(tree);
-        let node = tree.create(Node::new_anonymous(NodeContent::RecMap));
-        tree.child(node, node_k);
-        tree.child(node, node_t);
-        node
+        map_recipe::<K, T>(tree, false)
+    }
+}
+
+// Entries are sorted, so the output is the same as the serialization of the map.
+impl<K: Recipe, T: Recipe> Recipe for BTreeMap<K, T> {
+    fn recipe(tree: &mut NodeTree) -> u32 {
+        map_recipe::<K, T>(tree, true)
     }
 }
 
@@ -188,10 +333,7 @@ macro_rules! tuple_impls {
                     let nid = tree.create(Node::new_anonymous(NodeContent::RecTuple));
                     $(
                         let t = $T::recipe(tree);
-                        tree.create_with_parent(
-                            Some(nid),
-                            Node::new_anonymous(NodeContent::RecTupleMember { tid: RecTypeId::Id(t) })
-                        );
+                        tree.create_tuple_member(nid, t);
                     )+
                     nid
                 }
@@ -303,4 +445,70 @@ tuple_impls! {
         (10) -> K
         (11) -> L
     }
+    Tuple13 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+        (12) -> M
+    }
+    Tuple14 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+        (12) -> M
+        (13) -> N
+    }
+    Tuple15 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+        (12) -> M
+        (13) -> N
+        (14) -> O
+    }
+    Tuple16 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+        (12) -> M
+        (13) -> N
+        (14) -> O
+        (15) -> P
+    }
 }
@@ -31,7 +31,7 @@ impl<T> TreeItem<T> {
     ///
     /// Panics if there is not exactly on child.
     pub fn unique_child(&self) -> u32 {
-        if let Some(id) = self.children.get(0) {
+        if let Some(id) = self.children.first() {
             *id
         } else {
             panic!()
@@ -44,7 +44,7 @@ impl<T> TreeItem<T> {
     pub fn unique_child_or_none(&self) -> Option<u32> {
         let len = self.children.len();
         assert!(len <= 1);
-        self.children.get(0).cloned()
+        self.children.first().cloned()
     }
 }
 
@@ -81,10 +81,10 @@ impl<T> Tree<T> {
         let id = self.next_id;
         self.next_id += 1;
         let item = TreeItem {
-            id: id,
-            parent: parent,
+            id,
+            parent,
             children: Vec::new(),
-            value: value,
+            value,
         };
         self.items.insert(id, item);
         // If a parent is defined, verify it exists and add the new item as a child
@@ -152,7 +152,7 @@ impl<T> Tree<T> {
     /// * `parent_id` - Id of the parent node
     /// * `child_id` - Id of the child node
     pub fn child(&mut self, parent_id: u32, child_id: u32) {
-        let mut child = self.get_item_mut(child_id);
+        let child = self.get_item_mut(child_id);
         assert!(child.parent.is_none());
         child.parent = Some(parent_id);
         let parent = self.get_item_mut(parent_id);
@@ -1,10 +1,15 @@
-use bakery::{load_from_string, write_from_string_with_recipe, Recipe};
+#![allow(dead_code)]
+
+use bakery::{
+    load_from_string, write_from_string, write_from_string_with_recipe, Diagnostic, LoadError,
+    Recipe,
+};
 use core::fmt::Debug;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// AND two vectors of bytes
-pub fn vec_and(a: &Vec<u8>, b: &Vec<u8>) -> Vec<u8> {
+pub fn vec_and(a: &[u8], b: &[u8]) -> Vec<u8> {
     assert_eq!(a.len(), b.len());
     let mut result = a.to_vec();
     for (x, y) in result.iter_mut().zip(b) {
@@ -13,6 +18,59 @@ pub fn vec_and(a: &Vec<u8>, b: &Vec<u8>) -> Vec<u8> {
     result
 }
 
+/// Compile data according to a recipe and check the binary result.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+/// * `expect` - Expected binary result
+pub fn test_compile(rec: &str, dat: &str, expect: &[u8]) {
+    let mut out = Vec::<u8>::new();
+    write_from_string_with_recipe(&mut out, rec, dat).unwrap();
+    assert_eq!(out, expect);
+}
+
+/// Compile data according to a recipe and check that compilation fails.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+pub fn test_compile_error(rec: &str, dat: &str) {
+    let mut out = Vec::<u8>::new();
+    assert!(write_from_string_with_recipe(&mut out, rec, dat).is_err());
+}
+
+/// Compile data according to a recipe, check that compilation fails and return the reported
+/// diagnostics.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+pub fn compile_diagnostics(rec: &str, dat: &str) -> Vec<Diagnostic> {
+    let mut out = Vec::<u8>::new();
+    match write_from_string_with_recipe(&mut out, rec, dat) {
+        Err(LoadError::CompilationErrors(diagnostics)) => diagnostics,
+        r => panic!("expected compilation errors, got {:?}", r),
+    }
+}
+
+/// Compile data according to a recipe and check that compilation fails with a single error of
+/// the given code.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+/// * `code` - Expected diagnostic code
+pub fn test_compile_error_code(rec: &str, dat: &str, code: &str) {
+    let diagnostics = compile_diagnostics(rec, dat);
+    let codes: Vec<&str> = diagnostics.iter().map(Diagnostic::code).collect();
+    assert_eq!(codes, [code]);
+}
+
 /// Similar to `test_compile` but test only a subset of the resulting bytes
 /// Length of `mask` must be equal to length of `expect`.
 ///
@@ -29,7 +87,7 @@ pub fn test_compile_mask(rec: &str, dat: &str, expect: &[u8], mask: &[u8]) -> Ve
     let mut out_masked = out.clone();
     assert_eq!(expect.len(), mask.len()); // Required as zip won't check that
     for (a, b) in out_masked.iter_mut().zip(mask) {
-        *a = *a & b;
+        *a &= b;
     }
     assert_eq!(out_masked, expect);
     out
@@ -55,6 +113,13 @@ where
         assert_eq!(bincode::serialize(&val).unwrap(), bin);
     }
     assert_eq!(bincode::deserialize::<T>(&out).unwrap(), val);
+    // The recipe built by the Recipe trait must produce the bincode encoding of the value.
+    let mut out = Vec::<u8>::new();
+    write_from_string::<T>(&mut out, dat).unwrap();
+    if bin.is_some() {
+        assert_eq!(out, bincode::serialize(&val).unwrap());
+    }
+    assert_eq!(bincode::deserialize::<T>(&out).unwrap(), val);
     assert_eq!(load_from_string::<T>(dat).unwrap(), val);
 }
 
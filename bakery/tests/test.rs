@@ -1,7 +1,9 @@
 use hex_literal::hex;
 use std::path::Path;
 mod common;
-use bakery::{load_from_file, load_from_file_with_recipe, load_from_string};
+use bakery::{
+    load_from_file, load_from_file_with_recipe, load_from_string, LoadError, NodeTree, Recipe,
+};
 use bakery_derive::*;
 use common::test_compile_ser;
 use serde::Deserialize;
@@ -118,6 +120,7 @@ fn test_load_from_file() {
             .unwrap(),
     )
     .unwrap();
+    std::fs::remove_file(Path::new("tests").join("test.deps")).unwrap();
 
     let s: S = load_from_file(
         Path::new("tests")
@@ -145,3 +148,21 @@ fn test_load_from_file() {
     )
     .unwrap();
 }
+
+#[test]
+fn test_deserialize_error() {
+    // Recipe which does not match the deserialized type
+    #[derive(Deserialize, Debug)]
+    struct Wide(u32);
+
+    impl Recipe for Wide {
+        fn recipe(tree: &mut NodeTree) -> u32 {
+            u8::recipe(tree)
+        }
+    }
+
+    assert!(matches!(
+        load_from_string::<Wide>("1").map(|w| w.0),
+        Err(LoadError::DeserializeError(_))
+    ));
+}
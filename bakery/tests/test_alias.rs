@@ -0,0 +1,81 @@
+use hex_literal::hex;
+mod common;
+use common::{compile_diagnostics, test_compile, test_compile_error};
+
+#[test]
+fn test_alias() {
+    test_compile(
+        "struct { type Grid = Map<u8, List<u8>>, g: Grid }",
+        "g: {1: [2, 3]}",
+        &hex!("0100000000000000 01 0200000000000000 02 03"),
+    );
+    // Aliases can refer to other aliases and to structures
+    test_compile(
+        "struct { struct P { x: u8 }, type Q = P, type R = (Q, Q), r: R }",
+        "r: ({x: 1}, {x: 2})",
+        &hex!("01 02"),
+    );
+    // Aliases can be declared after their use
+    test_compile("struct { a: A, type A = u16 }", "a: 1", &hex!("0100"));
+}
+
+#[test]
+fn test_alias_semicolon() {
+    // Aliases can be terminated with `;`, without separator
+    test_compile(
+        "struct { type Pair<T> = (T, T); type Id = u16; a: Pair<Id>, b: u8 }",
+        "a: (1, 2), b: 3",
+        &hex!("0100 0200 03"),
+    );
+    test_compile("struct { a: A, type A = u16; }", "a: 1", &hex!("0100"));
+    test_compile(
+        "struct { mod m { type Id = u16; type Ids = List<Id>; }, a: m::Ids }",
+        "a: [1]",
+        &hex!("0100000000000000 0100"),
+    );
+    // Other declarations still require a separator
+    test_compile_error("struct { type Id = u16; a: Id b: Id }", "a: 1, b: 2");
+    test_compile_error("struct { type Id = u16;, a: Id }", "a: 1");
+}
+
+#[test]
+fn test_generic_alias() {
+    let rec = "struct { type Pair<T> = (T, T), a: Pair<u8>, b: Pair<u16> }";
+    test_compile(rec, "a: (1, 2), b: (3, 4)", &hex!("01 02 0300 0400"));
+    // Generic parameters of the enclosing structure are visible
+    test_compile(
+        "struct { struct S<T> { type L = List<T>, l: L }, s: S<u8> }",
+        "s: {l: [1]}",
+        &hex!("0100000000000000 01"),
+    );
+    test_compile_error(rec, "a: (1, 2), b: (3, 70000)");
+    // Wrong number of generic arguments
+    let diagnostics = compile_diagnostics("struct { type Pair<T> = (T, T), a: Pair }", "a: (1, 2)");
+    assert_eq!(diagnostics[0].code(), "E0017");
+}
+
+#[test]
+fn test_alias_path() {
+    test_compile(
+        "struct { mod m { type Id = u16 }, a: m::Id }",
+        "a: 2",
+        &hex!("0200"),
+    );
+    // Struct members can be omitted when the alias is an option
+    test_compile(
+        "struct { type Maybe<T> = Option<T>, a: Maybe<u8>, b: u8 }",
+        "b: 1",
+        &hex!("00 01"),
+    );
+}
+
+#[test]
+fn test_alias_cycle() {
+    let diagnostics = compile_diagnostics("struct { type A = B, type B = A, a: A }", "a: 1");
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics.iter().all(|d| d.code() == "E0028"));
+    let diagnostics = compile_diagnostics("struct { type A = List<A>, a: u8 }", "a: 1");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "E0028");
+    assert_eq!(diagnostics[0].location().unwrap().column(), 10);
+}
@@ -0,0 +1,58 @@
+use hex_literal::hex;
+mod common;
+use bakery::write_from_string_with_recipe;
+use bakery_derive::Recipe;
+use common::test_compile_ser;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_array() {
+    test_compile_ser(
+        "[u8; 4]",
+        "[1, 2, 3, 4]",
+        Some(&hex!("01020304")),
+        [1u8, 2, 3, 4],
+    );
+    test_compile_ser(
+        "[f32; 4]",
+        "[0, 1, 0.5, -2]",
+        Some(&hex!("00000000 0000803f 0000003f 000000c0")),
+        [0f32, 1.0, 0.5, -2.0],
+    );
+    test_compile_ser("[u8; 0]", "[]", Some(&hex!("")), [0u8; 0]);
+    test_compile_ser(
+        "[[u8; 2]; 3]",
+        "[[1, 2], [3, 4], [5, 6]]",
+        Some(&hex!("010203040506")),
+        [[1u8, 2], [3, 4], [5, 6]],
+    );
+}
+
+#[test]
+fn test_array_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Color {
+        name: String,
+        rgba: [u8; 4],
+    }
+
+    test_compile_ser(
+        "struct { name: String, rgba: [u8; 4] }",
+        "name: \"red\", rgba: [255, 0, 0, 255]",
+        Some(&hex!("0300000000000000 726564 ff0000ff")),
+        Color {
+            name: "red".to_string(),
+            rgba: [255, 0, 0, 255],
+        },
+    );
+}
+
+#[test]
+fn test_array_size_mismatch() {
+    let mut out = Vec::<u8>::new();
+    let rec = "[u8; 3]";
+    assert!(write_from_string_with_recipe(&mut out, rec, "[1, 2]").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "[1, 2, 3, 4]").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "[]").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "(1, 2, 3)").is_err());
+}
@@ -1,6 +1,6 @@
 use hex_literal::hex;
 mod common;
-use common::test_compile_ser;
+use common::{test_compile_error_code, test_compile_ser};
 
 #[test]
 fn test_bool() {
@@ -8,3 +8,10 @@ fn test_bool() {
     test_compile_ser(rec, "false", Some(&hex!("00")), false);
     test_compile_ser(rec, "true", Some(&hex!("01")), true);
 }
+
+#[test]
+fn test_bool_invalid_data() {
+    test_compile_error_code("struct { a: bool }", "a: 5", "E0014");
+    test_compile_error_code("struct { a: bool }", "a: 1.5", "E0014");
+    test_compile_error_code("struct { a: bool }", "a: \"true\"", "E0014");
+}
@@ -0,0 +1,64 @@
+use hex_literal::hex;
+mod common;
+use bakery_derive::Recipe;
+use common::{test_compile_error, test_compile_error_code, test_compile_ser};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[test]
+fn test_char() {
+    let rec = "char";
+    test_compile_ser(rec, "'a'", Some(&hex!("61")), 'a');
+    test_compile_ser(rec, "' '", Some(&hex!("20")), ' ');
+    test_compile_ser(rec, "'\"'", Some(&hex!("22")), '"');
+    test_compile_ser(rec, "'é'", Some(&hex!("c3a9")), 'é');
+    test_compile_ser(rec, "'😀'", Some(&hex!("f09f9880")), '😀');
+
+    // Escape sequences
+    test_compile_ser(rec, r"'\n'", Some(&hex!("0a")), '\n');
+    test_compile_ser(rec, r"'\''", Some(&hex!("27")), '\'');
+    test_compile_ser(rec, r"'\\'", Some(&hex!("5c")), '\\');
+    test_compile_ser(rec, r"'\0'", Some(&hex!("00")), '\0');
+    test_compile_ser(rec, r"'\u{1F600}'", Some(&hex!("f09f9880")), '😀');
+    test_compile_ser(rec, r"'\u{10ffff}'", Some(&hex!("f48fbfbf")), '\u{10ffff}');
+}
+
+#[test]
+fn test_char_invalid() {
+    let rec = "char";
+    // Surrogates are not unicode scalar values
+    test_compile_error_code(rec, r"'\u{d800}'", "E0018");
+    test_compile_error_code(rec, r"'\u{dfff}'", "E0018");
+    test_compile_error_code(rec, r"'\u{110000}'", "E0018");
+    // Exactly one character
+    test_compile_error(rec, "''");
+    test_compile_error(rec, "'ab'");
+    test_compile_error(rec, "'''");
+    // Not a character
+    test_compile_error_code(rec, "\"a\"", "E0027");
+    test_compile_error_code(rec, "97", "E0027");
+    test_compile_error_code("String", "'a'", "E0012");
+}
+
+#[test]
+fn test_char_keybindings() {
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq)]
+    enum Action {
+        Jump,
+        Fire,
+    }
+
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq)]
+    struct KeyBindings {
+        keys: HashMap<char, Action>,
+    }
+
+    test_compile_ser(
+        "struct { enum Action { Jump, Fire }, keys: Map<char, Action> }",
+        "keys: {' ': Jump}",
+        Some(&hex!("0100000000000000 20 00000000")),
+        KeyBindings {
+            keys: vec![(' ', Action::Jump)].into_iter().collect(),
+        },
+    );
+}
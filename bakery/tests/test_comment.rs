@@ -0,0 +1,130 @@
+use hex_literal::hex;
+mod common;
+use bakery::load_from_string;
+use bakery_derive::Recipe;
+use common::test_compile_ser;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[test]
+fn test_line_comment() {
+    test_compile_ser("// Type\ni8", "// Value\n42", Some(&hex!("2a")), 42i8);
+    test_compile_ser("i8 // Type", "42 // Value", Some(&hex!("2a")), 42i8);
+    test_compile_ser("i8 //", "42 //", Some(&hex!("2a")), 42i8);
+    test_compile_ser("i8\n// Type\n", "42\n// Value\n", Some(&hex!("2a")), 42i8);
+}
+
+#[test]
+fn test_block_comment() {
+    test_compile_ser("/* Type */ i8", "/* Value */ 42", Some(&hex!("2a")), 42i8);
+    test_compile_ser("i8 /* Type */", "42 /* Value */", Some(&hex!("2a")), 42i8);
+    test_compile_ser("/**/i8/**/", "/**/42/**/", Some(&hex!("2a")), 42i8);
+    test_compile_ser(
+        "/* multi\nline */ i8",
+        "/* multi\nline */ 42",
+        Some(&hex!("2a")),
+        42i8,
+    );
+    // Line comment markers have no effect in block comments
+    test_compile_ser("/* // */ i8", "/* // */ 42", Some(&hex!("2a")), 42i8);
+}
+
+#[test]
+fn test_nested_block_comment() {
+    test_compile_ser(
+        "/* a /* b */ c */ i8",
+        "/* a /* b /* c */ */ d */ 42",
+        Some(&hex!("2a")),
+        42i8,
+    );
+    test_compile_ser(
+        "/* /* */ i8 */ u8",
+        "/* /* */ 1 */ 42",
+        Some(&hex!("2a")),
+        42u8,
+    );
+}
+
+#[test]
+fn test_comment_values() {
+    test_compile_ser(
+        "f32 // Type",
+        "/* Value */ 1.5 // Value",
+        Some(&hex!("0000c03f")),
+        1.5f32,
+    );
+    test_compile_ser(
+        "bool // Type",
+        "/* Value */ true // Value",
+        Some(&hex!("01")),
+        true,
+    );
+    test_compile_ser(
+        "String",
+        "/* a */ \"/* b */ // c\" // d",
+        None,
+        "/* b */ // c".to_string(),
+    );
+    test_compile_ser(
+        "(u8, /* Second */ u8)",
+        "(1, // First\n 2 /* Second */)",
+        Some(&hex!("0102")),
+        (1u8, 2u8),
+    );
+    test_compile_ser(
+        "List</* Item */ u8>",
+        "[1, /* 2, */ 3 // 4\n]",
+        Some(&hex!("02000000000000000103")),
+        vec![1u8, 3],
+    );
+    test_compile_ser(
+        "Map<u8, u8>",
+        "{ /* Key */ 1: /* Value */ 2 // Entry\n }",
+        Some(&hex!("01000000000000000102")),
+        [(1, 2)].iter().cloned().collect::<HashMap<u8, u8>>(),
+    );
+}
+
+#[test]
+fn test_comment_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        A,
+        B(u8),
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct S {
+        x: u8,
+        e: E,
+    }
+
+    let rec = "
+        // Structure description
+        struct {
+            enum E {
+                A, // First value
+                B(u8) /* Second value */
+            },
+            /* Position */
+            x: u8,
+            e: E // Last member
+        }
+        // End of file";
+    let dat = "
+        // Settings
+        x: 10, // Comment
+        /* e: A, */
+        e: B /* Comment */ (20)
+        // End of file";
+    test_compile_ser(
+        rec,
+        dat,
+        Some(&hex!("0a0100000014")),
+        S { x: 10, e: E::B(20) },
+    );
+    assert_eq!(
+        load_from_string::<S>("x: /* 5 */ 6, e: A // Comment").unwrap(),
+        S { x: 6, e: E::A }
+    );
+}
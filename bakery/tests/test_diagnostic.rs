@@ -0,0 +1,123 @@
+use bakery::{load_from_string, LoadError, Renderer, Severity};
+mod common;
+use common::compile_diagnostics;
+
+#[test]
+fn test_diagnostic() {
+    let diagnostics = compile_diagnostics("struct { a: u8,\n  b: u8 }", "a: 1,\nb: 256");
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(d.severity(), Severity::Error);
+    assert_eq!(d.code(), "E0023");
+    assert!(d.message().ends_with("out of bounds"));
+    let location = d.location().unwrap();
+    assert_eq!((location.line(), location.column()), (2, 4));
+    assert_eq!(&location.source()[location.start()..location.end()], "256");
+    assert!(d.related().is_empty());
+    assert_eq!(
+        d.to_string(),
+        format!("error[E0023]: {}\n --> <string>:2:4", d.message())
+    );
+
+    // All errors are reported
+    let diagnostics = compile_diagnostics("struct { a: u8, b: u8 }", "a: 1000, b: 2000");
+    assert_eq!(diagnostics.len(), 2);
+}
+
+#[test]
+fn test_diagnostic_related() {
+    let diagnostics = compile_diagnostics("struct { a: u8 }", "a: 1,\na: 2");
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(d.code(), "E0019");
+    let location = d.location().unwrap();
+    assert_eq!((location.line(), location.column()), (2, 1));
+    assert_eq!(d.related().len(), 1);
+    let related = &d.related()[0];
+    assert_eq!(related.message(), "first defined here");
+    assert_eq!(
+        (related.location().line(), related.location().column()),
+        (1, 1)
+    );
+
+    let diagnostics = compile_diagnostics("enum { A = 1, B = 1 }", "A");
+    assert_eq!(diagnostics[0].code(), "E0005");
+    assert_eq!(diagnostics[0].location().unwrap().column(), 15);
+    assert_eq!(diagnostics[0].related()[0].location().column(), 8);
+}
+
+#[test]
+fn test_diagnostic_derived_recipe() {
+    // Nodes of recipes built with the Recipe trait have no location, data nodes do.
+    match load_from_string::<(u8, u8)>("(1, 2, 3)") {
+        Err(LoadError::CompilationErrors(diagnostics)) => {
+            assert_eq!(diagnostics[0].code(), "E0020");
+            assert!(diagnostics[0].location().is_some());
+            assert!(diagnostics[0].related().is_empty());
+        }
+        r => panic!("expected compilation errors, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_render() {
+    let diagnostics = compile_diagnostics("struct { a: u8 }", "a: 1,\na: 2");
+    let d = &diagnostics[0];
+    assert_eq!(
+        Renderer::new().render(d),
+        format!(
+            "error[E0019]: {}
+ --> <string>:2:1
+  |
+1 | a: 1,
+  | ---- first defined here
+2 | a: 2
+  | ^^^^
+",
+            d.message()
+        )
+    );
+
+    // Lines between labels are elided
+    let diagnostics = compile_diagnostics("struct { a: u8 }", "a: 1,\n\n\n\n\n\n\n\n\na: 2");
+    let d = &diagnostics[0];
+    assert_eq!(
+        Renderer::new().render(d),
+        format!(
+            "error[E0019]: {}
+  --> <string>:10:1
+   |
+ 1 | a: 1,
+   | ---- first defined here
+...
+10 | a: 2
+   | ^^^^
+",
+            d.message()
+        )
+    );
+
+    // Recipe and data locations are shown in separate excerpts
+    let diagnostics = compile_diagnostics("(u8, u8)", "(1, 2, 3)");
+    let d = &diagnostics[0];
+    assert_eq!(
+        Renderer::new().render(d),
+        format!(
+            "error[E0020]: {}
+ --> <string>:1:1
+  |
+1 | (1, 2, 3)
+  | ^^^^^^^^^
+ ::: <string>:1:1
+  |
+1 | (u8, u8)
+  | -------- tuple declared here
+",
+            d.message()
+        )
+    );
+
+    // Colours are disabled by default
+    assert!(!Renderer::new().render(d).contains('\x1b'));
+    assert!(Renderer::new().color(true).render(d).contains("\x1b[1;31m"));
+}
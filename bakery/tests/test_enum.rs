@@ -1,8 +1,13 @@
 use hex_literal::hex;
 mod common;
 use bakery_derive::Recipe;
-use common::test_compile_ser;
+use common::{
+    compile_diagnostics, test_compile, test_compile_error, test_compile_error_code,
+    test_compile_ser,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 
 #[test]
 fn test_basic_enum() {
@@ -18,6 +23,40 @@ fn test_basic_enum() {
     test_compile_ser(rec, "C", Some(&hex!("02000000")), E::C);
 }
 
+#[test]
+fn test_enum_in_generic_types() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        A,
+        B,
+        C,
+    }
+    test_compile_ser(
+        "List<enum { A, B, C }>",
+        "[C, A]",
+        Some(&hex!("0200000000000000 02000000 00000000")),
+        vec![E::C, E::A],
+    );
+    test_compile_ser(
+        "Option<enum { A, B, C }>",
+        "Some(B)",
+        Some(&hex!("01 01000000")),
+        Some(E::B),
+    );
+    test_compile_ser(
+        "Map<u8, enum { A, B, C }>",
+        "{1: C}",
+        Some(&hex!("0100000000000000 01 02000000")),
+        vec![(1, E::C)].into_iter().collect::<HashMap<u8, E>>(),
+    );
+    test_compile_ser(
+        "Box<enum { A, B, C }>",
+        "C",
+        Some(&hex!("02000000")),
+        Box::new(E::C),
+    );
+}
+
 #[test]
 fn test_enum_tuple() {
     #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
@@ -95,3 +134,224 @@ fn test_inline_enum() {
     test_compile_ser(rec, "B", Some(&hex!("01000000")), E::B);
     test_compile_ser(rec, "C", Some(&hex!("02000000")), E::C);
 }
+
+#[test]
+fn test_enum_storage_type() {
+    let rec = "enum: u8 { A, B, C }";
+    test_compile(rec, "A", &hex!("00"));
+    test_compile(rec, "C", &hex!("02"));
+    let rec = "enum: i64 { A, B(u8) }";
+    test_compile(rec, "B(7)", &hex!("010000000000000007"));
+    // Storage type must be an integer
+    test_compile_error("enum: f32 { A, B }", "A");
+    test_compile_error("enum: Unknown { A, B }", "A");
+
+    let rec = "struct { enum Flags: u16 { A, B }, f: Flags }";
+    test_compile(rec, "f: B", &hex!("0100"));
+}
+
+#[test]
+fn test_enum_discriminant() {
+    let rec = "enum: u8 { A = 1, B = 4, C }";
+    test_compile(rec, "A", &hex!("01"));
+    test_compile(rec, "B", &hex!("04"));
+    test_compile(rec, "C", &hex!("05"));
+
+    // Values following an explicit discriminant are incremented from it
+    let rec = "enum { A, B = 10, C, D = 3, E }";
+    test_compile(rec, "A", &hex!("00000000"));
+    test_compile(rec, "C", &hex!("0b000000"));
+    test_compile(rec, "E", &hex!("04000000"));
+
+    let rec = "enum: i8 { A = -128, B, C = -1, D }";
+    test_compile(rec, "A", &hex!("80"));
+    test_compile(rec, "B", &hex!("81"));
+    test_compile(rec, "C", &hex!("ff"));
+    test_compile(rec, "D", &hex!("00"));
+
+    // Discriminants with associated data
+    let rec = "enum: u8 { A(u8) = 2, B { x: u8 } = 5, C }";
+    test_compile(rec, "A(1)", &hex!("0201"));
+    test_compile(rec, "B { x: 3 }", &hex!("0503"));
+    test_compile(rec, "C", &hex!("06"));
+}
+
+#[test]
+fn test_derived_enum_discriminant() {
+    // Without repr, serde encodes the variant index, so discriminants must match it.
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        A = 0,
+        B = 1,
+        C,
+    }
+    let rec = "enum { A, B, C }";
+    test_compile_ser(rec, "C", Some(&hex!("02000000")), E::C);
+
+    // A repr alone does not change the serde encoding.
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[repr(u8)]
+    enum Mode {
+        Off,
+        On,
+    }
+    let rec = "enum { Off, On }";
+    test_compile_ser(rec, "On", Some(&hex!("01000000")), Mode::On);
+
+    // Unit-only enums can opt in to be encoded with their discriminant, as serde_repr does.
+    #[derive(Recipe, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+    #[serde(try_from = "i8", into = "i8")]
+    #[bakery(repr)]
+    #[repr(i8)]
+    enum Level {
+        Low = -1,
+        Medium,
+        High = 10,
+        VeryHigh,
+    }
+
+    impl From<Level> for i8 {
+        fn from(level: Level) -> i8 {
+            level as i8
+        }
+    }
+
+    impl TryFrom<i8> for Level {
+        type Error = String;
+
+        fn try_from(value: i8) -> Result<Level, String> {
+            [Level::Low, Level::Medium, Level::High, Level::VeryHigh]
+                .iter()
+                .copied()
+                .find(|&level| level as i8 == value)
+                .ok_or_else(|| format!("invalid level {}", value))
+        }
+    }
+
+    let rec = "enum: i8 { Low = -1, Medium, High = 10, VeryHigh }";
+    test_compile_ser(rec, "Low", Some(&hex!("ff")), Level::Low);
+    test_compile_ser(rec, "Medium", Some(&hex!("00")), Level::Medium);
+    test_compile_ser(rec, "High", Some(&hex!("0a")), Level::High);
+    test_compile_ser(rec, "VeryHigh", Some(&hex!("0b")), Level::VeryHigh);
+
+    // Enums with data are always encoded with the variant index.
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[repr(u8)]
+    enum Command {
+        Stop,
+        Move(u8),
+    }
+    let rec = "enum { Stop, Move(u8) }";
+    test_compile_ser(rec, "Move(3)", Some(&hex!("01000000 03")), Command::Move(3));
+}
+
+#[test]
+fn test_enum_discriminant_errors() {
+    let codes = |rec| {
+        compile_diagnostics(rec, "A")
+            .iter()
+            .map(|d| d.code().to_string())
+            .collect::<Vec<_>>()
+    };
+    // Duplicate values
+    assert_eq!(codes("enum { A = 1, B = 1 }"), ["E0005"]);
+    assert_eq!(codes("enum { A, B, C = 1 }"), ["E0005"]);
+    assert_eq!(codes("enum { A = 5, B = 3, C, D }"), ["E0005"]);
+    // Values which don't fit the storage type
+    assert_eq!(codes("enum: u8 { A = 256 }"), ["E0004"]);
+    assert_eq!(codes("enum: u8 { A = -1 }"), ["E0004"]);
+    assert_eq!(codes("enum: u8 { A = 255, B }"), ["E0004"]);
+    assert_eq!(codes("enum: i8 { A = -129 }"), ["E0004"]);
+}
+
+#[test]
+fn test_generic_enum() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct S {
+        a: Either<u8, u16>,
+        b: Either<u8, u16>,
+    }
+
+    let rec = "struct {
+        enum Either<L, R> { Left(L), Right(R) },
+        a: Either<u8, u16>,
+        b: Either<u8, u16>
+    }";
+    test_compile_ser(
+        rec,
+        "a: Left(1), b: Right(2)",
+        Some(&hex!("00000000 01 01000000 0200")),
+        S {
+            a: Either::Left(1),
+            b: Either::Right(2),
+        },
+    );
+
+    // Generic types in structure values, storage type, and nested generic types
+    let rec = "struct {
+        enum Tagged<T>: u8 { Empty, Item { value: T, count: u8 } },
+        struct Wrapper<T> { inner: Tagged<T> },
+        w: Wrapper<i16>,
+        l: List<Tagged<bool>>
+    }";
+    test_compile(
+        rec,
+        "w: { inner: Item { value: -2, count: 3 } }, l: [Empty, Item { value: true, count: 1 }]",
+        &hex!("01feff03 0200000000000000 00 010101"),
+    );
+}
+
+#[test]
+fn test_generic_enum_errors() {
+    let rec = "struct { enum Either<L, R> { Left(L), Right(R) }, a: Either<u8> }";
+    let diagnostics = compile_diagnostics(rec, "a: Left(1)");
+    assert_eq!(diagnostics[0].code(), "E0017");
+    let rec = "struct { enum E { A, B }, a: E<u8> }";
+    let diagnostics = compile_diagnostics(rec, "a: A");
+    assert_eq!(diagnostics[0].code(), "E0017");
+    // Generic types are not enumeration values
+    let rec = "struct { enum Either<L, R> { Left(L), Right(R) }, a: Either<u8, u8> }";
+    let diagnostics = compile_diagnostics(rec, "a: L");
+    assert_eq!(diagnostics[0].code(), "E0006");
+}
+
+#[test]
+fn test_enum_type_expressions() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        A(Vec<f32>),
+        B((u8, u8), Option<u8>),
+    }
+    let rec = "enum { A(List<f32>), B((u8, u8), Option<u8>) }";
+    test_compile_ser(
+        rec,
+        "A([1.5])",
+        Some(&hex!("00000000 0100000000000000 0000c03f")),
+        E::A(vec![1.5]),
+    );
+    test_compile_ser(
+        rec,
+        "B((1, 2), None)",
+        Some(&hex!("01000000 0102 00")),
+        E::B((1, 2), None),
+    );
+}
+
+#[test]
+fn test_enum_invalid_data() {
+    // Enumerations expect an identifier
+    test_compile_error_code("enum { A, B }", "1", "E0014");
+    test_compile_error_code("struct { a: enum { A, B(u8) } }", "a: [1]", "E0014");
+}
+
+#[test]
+fn test_empty_enum() {
+    test_compile_error("enum { }", "A");
+    test_compile_error("enum: u8 { }", "A");
+}
@@ -1,3 +1,6 @@
+// Test vectors are written with full precision on purpose.
+#![allow(clippy::excessive_precision, clippy::approx_constant)]
+
 use bakery::write_from_string_with_recipe;
 use hex_literal::hex;
 mod common;
@@ -50,10 +53,10 @@ fn test_compile_nan<T>(
     let mut out_masked = out.clone();
     assert_eq!(expect.len(), mask.len()); // Required as zip won't check that
     for (a, b) in out_masked.iter_mut().zip(mask) {
-        *a = *a & b;
+        *a &= b;
     }
     assert_eq!(out_masked, expect);
-    assert!(vec_and(&out, &mantissa_mask.to_vec()) != mantissa_expect);
+    assert!(vec_and(&out, mantissa_mask) != mantissa_expect);
     assert!(bincode::deserialize::<T>(&out).unwrap().float_is_nan());
     assert!(load_from_string::<T>(dat).unwrap().float_is_nan());
 }
@@ -159,8 +162,8 @@ fn test_f64() {
         rec,
         &hex!("000000000000f07f"),
         &hex!("000000000000f07f"),
-        &hex!("0000000000000000").to_vec(),
-        &hex!("ffffffffffff0f00").to_vec(),
+        &hex!("0000000000000000"),
+        &hex!("ffffffffffff0f00"),
     );
 
     // Infinity
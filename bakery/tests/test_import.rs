@@ -0,0 +1,200 @@
+use bakery::{load_from_file_with_recipe, write_from_string_with_recipe_and_options, LoadOptions};
+use hex_literal::hex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+mod common;
+use common::{test_compile, test_compile_error};
+
+/// Options with the `tests/import` directory as search path
+fn import_options() -> LoadOptions {
+    LoadOptions {
+        search_paths: vec![Path::new("tests").join("import")],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_mod() {
+    test_compile(
+        "struct { mod a { struct S { x: u8 } }, s: a::S }",
+        "s: { x: 1 }",
+        &hex!("01"),
+    );
+
+    // Nested modules
+    test_compile(
+        "struct { mod a { mod b { struct S { x: u8 } } }, s: a::b::S }",
+        "s: { x: 1 }",
+        &hex!("01"),
+    );
+
+    // Types declared in structures can be accessed with paths as well
+    test_compile(
+        "struct { struct A { enum E: u8 { X, Y } }, e: A::E }",
+        "e: Y",
+        &hex!("01"),
+    );
+
+    // Types in a module can use types of the parent scopes
+    test_compile(
+        "struct { struct V { x: u8 }, mod a { struct S { v: V } }, s: a::S }",
+        "s: { v: { x: 1 } }",
+        &hex!("01"),
+    );
+
+    // Empty module
+    test_compile("struct { mod a {}, x: u8 }", "x: 1", &hex!("01"));
+}
+
+#[test]
+fn test_mod_invalid() {
+    // A module is not a type
+    test_compile_error("struct { mod a { struct S { x: u8 } }, s: a }", "s: {}");
+    // Undefined type in module
+    test_compile_error("struct { mod a { struct S { x: u8 } }, s: a::T }", "s: {}");
+    // Modules can't have members
+    test_compile_error("struct { mod a { x: u8 } }", "");
+    // Members are not types
+    test_compile_error("struct { struct A { x: u8 }, s: A::x }", "s: 1");
+}
+
+#[test]
+fn test_import() {
+    let mut out = Vec::<u8>::new();
+    write_from_string_with_recipe_and_options(
+        &mut out,
+        "struct { import \"geometry.rec\", p: geometry::Point, l: geometry::units::Length }",
+        "p: { x: { mm: 1 }, y: { mm: 2 } }, l: { mm: 3 }",
+        &import_options(),
+    )
+    .unwrap();
+    assert_eq!(out, hex!("010002000300"));
+
+    // Not found without search path
+    let mut out = Vec::<u8>::new();
+    assert!(write_from_string_with_recipe_and_options(
+        &mut out,
+        "struct { import \"geometry.rec\", p: geometry::Point }",
+        "p: { x: { mm: 1 }, y: { mm: 2 } }",
+        &LoadOptions::default(),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_import_invalid() {
+    let options = import_options();
+    for rec in [
+        // Missing file
+        "struct { import \"missing.rec\" }",
+        // Cyclic imports
+        "struct { import \"cycle_a.rec\" }",
+        // Imported declarations are only reachable through the module
+        "struct { import \"units.rec\", l: Length }",
+    ] {
+        let mut out = Vec::<u8>::new();
+        assert!(write_from_string_with_recipe_and_options(&mut out, rec, "", &options).is_err());
+    }
+}
+
+#[test]
+fn test_load_from_file_with_import() {
+    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    struct Length {
+        mm: u16,
+    }
+
+    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    struct Point {
+        x: Length,
+        y: Length,
+    }
+
+    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    struct Rect {
+        origin: Point,
+        size: Point,
+    }
+
+    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    struct S {
+        a: Point,
+        b: Rect,
+    }
+
+    // Imports are resolved relative to the recipe file
+    let dir: PathBuf = Path::new("tests").join("import");
+    let s: S = load_from_file_with_recipe(
+        dir.join("main.rec").to_str().unwrap(),
+        dir.join("main.dat").to_str().unwrap(),
+    )
+    .unwrap();
+    let point = |x, y| Point {
+        x: Length { mm: x },
+        y: Length { mm: y },
+    };
+    assert_eq!(
+        s,
+        S {
+            a: point(1, 2),
+            b: Rect {
+                origin: point(3, 4),
+                size: point(5, 6)
+            }
+        }
+    );
+
+    // Remove cache for next test
+    std::fs::remove_file(dir.join("main.bin")).unwrap();
+    std::fs::remove_file(dir.join("main.deps")).unwrap();
+}
+
+#[test]
+fn test_load_from_file_cache() {
+    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    struct S {
+        a: u8,
+    }
+
+    let dir = std::env::temp_dir().join("bakery_test_load_from_file_cache");
+    std::fs::create_dir_all(&dir).unwrap();
+    let rec_path = dir.join("main.rec");
+    let dat_path = dir.join("main.dat");
+    let bin_path = dir.join("main.bin");
+    let load =
+        || load_from_file_with_recipe::<S>(rec_path.to_str().unwrap(), dat_path.to_str().unwrap());
+    // File modification dates may be too coarse to order files written in a row
+    let set_modified = |name: &str, secs: i64| {
+        let now = std::time::SystemTime::now();
+        let offset = std::time::Duration::from_secs(secs.unsigned_abs());
+        let time = if secs < 0 { now - offset } else { now + offset };
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join(name))
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    };
+    std::fs::write(&rec_path, "struct { import \"units.rec\", a: units::Unit }").unwrap();
+    std::fs::write(dir.join("units.rec"), "enum Unit: u8 { Mm, Cm }").unwrap();
+    let _ = std::fs::remove_file(&bin_path);
+
+    // Failed compilation does not leave a binary file
+    std::fs::write(&dat_path, "a: Km").unwrap();
+    assert!(load().is_err());
+    assert!(!bin_path.exists());
+
+    std::fs::write(&dat_path, "a: Cm").unwrap();
+    for name in ["main.rec", "main.dat", "units.rec"] {
+        set_modified(name, -10);
+    }
+    assert_eq!(load().unwrap(), S { a: 1 });
+    assert!(bin_path.exists());
+
+    // Changing an imported recipe file invalidates the binary file
+    std::fs::write(dir.join("units.rec"), "enum Unit: u8 { Cm, Mm }").unwrap();
+    set_modified("units.rec", 10);
+    assert_eq!(load().unwrap(), S { a: 0 });
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
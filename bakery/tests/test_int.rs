@@ -1,6 +1,6 @@
 use hex_literal::hex;
 mod common;
-use common::test_compile_ser;
+use common::{test_compile, test_compile_error, test_compile_error_code, test_compile_ser};
 
 #[test]
 fn test_i8() {
@@ -268,3 +268,133 @@ fn test_u64() {
         17063061512885227165u64,
     );
 }
+
+#[test]
+fn test_i128() {
+    let rec = "i128";
+    test_compile_ser(
+        rec,
+        "0",
+        Some(&hex!("00000000000000000000000000000000")),
+        0i128,
+    );
+    test_compile_ser(
+        rec,
+        "-1",
+        Some(&hex!("ffffffffffffffffffffffffffffffff")),
+        -1i128,
+    );
+    test_compile_ser(
+        rec,
+        "-170141183460469231731687303715884105728",
+        Some(&hex!("00000000000000000000000000000080")),
+        i128::MIN,
+    );
+    test_compile_ser(
+        rec,
+        "170141183460469231731687303715884105727",
+        Some(&hex!("ffffffffffffffffffffffffffffff7f")),
+        i128::MAX,
+    );
+    test_compile_error_code(rec, "170141183460469231731687303715884105728", "E0023");
+    test_compile_error_code(rec, "-170141183460469231731687303715884105729", "E0023");
+}
+
+#[test]
+fn test_u128() {
+    let rec = "u128";
+    test_compile_ser(
+        rec,
+        "0",
+        Some(&hex!("00000000000000000000000000000000")),
+        0u128,
+    );
+    test_compile_ser(
+        rec,
+        "340282366920938463463374607431768211455",
+        Some(&hex!("ffffffffffffffffffffffffffffffff")),
+        u128::MAX,
+    );
+    test_compile_ser(
+        rec,
+        "18446744073709551616",
+        Some(&hex!("00000000000000000100000000000000")),
+        1u128 << 64,
+    );
+    test_compile_error_code(rec, "340282366920938463463374607431768211456", "E0023");
+    test_compile_error_code(rec, "-1", "E0023");
+}
+
+#[test]
+fn test_int_arbitrary_width() {
+    test_compile("u24", "0", &hex!("000000"));
+    test_compile("u24", "16777215", &hex!("ffffff"));
+    test_compile("u24", "66051", &hex!("030201"));
+    test_compile_error_code("u24", "16777216", "E0023");
+    test_compile("i48", "-1", &hex!("ffffffffffff"));
+    test_compile("i48", "-140737488355328", &hex!("000000000080"));
+    test_compile("i48", "140737488355327", &hex!("ffffffffff7f"));
+    test_compile_error_code("i48", "140737488355328", "E0023");
+
+    // Widths which are not a multiple of 8 use the minimum number of whole bytes.
+    test_compile("u1", "1", &hex!("01"));
+    test_compile_error_code("u1", "2", "E0023");
+    test_compile("i12", "-2048", &hex!("00f8"));
+    test_compile("i12", "2047", &hex!("ff07"));
+    test_compile_error_code("i12", "2048", "E0023");
+    test_compile_error_code("i12", "-2049", "E0023");
+
+    // Arbitrary width types can be used anywhere a type is expected.
+    test_compile(
+        "struct { a: u24, b: List<i24>, c: u24 }",
+        "a: 1, b: [-1], c: 2",
+        &hex!("010000 0100000000000000 ffffff 020000"),
+    );
+    test_compile("enum: u24 { A, B = 70000 }", "B", &hex!("701101"));
+
+    // Invalid widths
+    test_compile_error_code("u0", "0", "E0022");
+    test_compile_error_code("u129", "0", "E0022");
+    test_compile_error_code("u024", "0", "E0022");
+    test_compile_error_code("i", "0", "E0022");
+}
+
+#[test]
+fn test_int_radix() {
+    test_compile_ser("u32", "0xFF0000FF", Some(&hex!("ff0000ff")), 0xFF0000FFu32);
+    test_compile_ser("u32", "0xff00_00ff", Some(&hex!("ff0000ff")), 0xFF0000FFu32);
+    test_compile_ser("u8", "0b1010_0001", Some(&hex!("a1")), 0b1010_0001u8);
+    test_compile_ser("u16", "0o755", Some(&hex!("ed01")), 0o755u16);
+    test_compile_ser("u32", "1_000_000", Some(&hex!("40420f00")), 1_000_000u32);
+    test_compile_ser("u8", "0x_f_", Some(&hex!("0f")), 0xfu8);
+
+    // Signs
+    test_compile_ser("i8", "-0x80", Some(&hex!("80")), -0x80i8);
+    test_compile_ser("i8", "+0x7f", Some(&hex!("7f")), 0x7fi8);
+    test_compile_ser("i16", "-0b1", Some(&hex!("ffff")), -1i16);
+    test_compile_ser("i16", "-0o1_0", Some(&hex!("f8ff")), -8i16);
+
+    // Bounds
+    test_compile_error_code("u8", "0x100", "E0023");
+    test_compile_error_code("i8", "0x80", "E0023");
+    test_compile_error_code("i8", "-0x81", "E0023");
+    test_compile_error_code("u8", "-0b1", "E0023");
+    test_compile(
+        "u128",
+        "0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff",
+        &hex!("ffffffffffffffffffffffffffffffff"),
+    );
+
+    // Invalid literals
+    test_compile_error("u8", "0x");
+    test_compile_error("u8", "0b2");
+    test_compile_error("u8", "0o8");
+    test_compile_error("u8", "0xfg");
+    test_compile_error("u8", "_1");
+    test_compile_error("u8", "0X1");
+
+    // Radix literals in enumeration discriminants and floats
+    test_compile("enum: u16 { A = 0x1_00, B }", "B", &hex!("0101"));
+    test_compile("f32", "0x10", &16f32.to_le_bytes());
+    test_compile("f64", "-1_000", &(-1000f64).to_le_bytes());
+}
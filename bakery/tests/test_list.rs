@@ -1,6 +1,6 @@
 use hex_literal::hex;
 mod common;
-use common::test_compile_ser;
+use common::{test_compile, test_compile_error_code, test_compile_ser};
 
 #[test]
 fn test_list() {
@@ -10,4 +10,39 @@ fn test_list() {
         Some(&hex!("040000000000000001020304")),
         vec![1u8, 2, 3, 4],
     );
+    test_compile_ser(
+        "List<u8>",
+        "[]",
+        Some(&hex!("0000000000000000")),
+        Vec::<u8>::new(),
+    );
+}
+
+#[test]
+fn test_list_type_expressions() {
+    test_compile_ser(
+        "List<[u8; 2]>",
+        "[[1, 2], [3, 4]]",
+        Some(&hex!("0200000000000000 0102 0304")),
+        vec![[1u8, 2], [3, 4]],
+    );
+    test_compile_ser(
+        "List<(u8, bool)>",
+        "[(1, true)]",
+        Some(&hex!("0100000000000000 0101")),
+        vec![(1u8, true)],
+    );
+    test_compile(
+        "List<struct { a: u8, b: u8 }>",
+        "[{ a: 1, b: 2 }]",
+        &hex!("0100000000000000 0102"),
+    );
+}
+
+#[test]
+fn test_list_invalid_data() {
+    test_compile_error_code("List<u8>", "5", "E0010");
+    test_compile_error_code("List<u8>", "(1, 2)", "E0010");
+    test_compile_error_code("List<u8>", "{1, 2}", "E0010");
+    test_compile_error_code("struct { a: List<u8> }", "a: A", "E0010");
 }
@@ -1,7 +1,12 @@
+use bakery::{
+    load_from_string_with_options, write_from_string_with_recipe_and_options, LoadOptions,
+};
+use bakery_derive::Recipe;
 use hex_literal::hex;
 mod common;
-use common::test_compile_ser;
-use std::collections::HashMap;
+use common::{compile_diagnostics, test_compile, test_compile_error, test_compile_ser};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 #[test]
 fn test_map() {
@@ -29,3 +34,218 @@ fn test_map() {
             .collect::<HashMap<u8, i32>>(),
     );
 }
+
+#[test]
+fn test_map_duplicate_key() {
+    let diagnostics = compile_diagnostics("Map<u8, u8>", "{1: 5, 1: 6}");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "E0025");
+    assert_eq!(diagnostics[0].location().unwrap().column(), 8);
+    assert_eq!(diagnostics[0].related()[0].location().column(), 2);
+
+    // Keys are compared by value
+    test_compile_error("Map<i8, u8>", "{10: 5, +10: 6}");
+    test_compile_error("Map<String, u8>", r#"{"a": 5, r"a": 6, "\u{61}": 7}"#);
+    assert_eq!(
+        compile_diagnostics("Map<String, u8>", r#"{"a": 5, r"a": 6, "\u{61}": 7}"#).len(),
+        2
+    );
+
+    // Enum and structure keys
+    test_compile_error(
+        "struct { enum E { A, B }, m: Map<E, u8> }",
+        "m: {A: 1, B: 2, A: 3}",
+    );
+    let rec = "struct { struct K { a: u8, b: bool }, m: Map<K, u8> }";
+    test_compile_error(rec, "m: {{a: 1, b: true}: 1, {b: true, a: 1}: 2}");
+    test_compile(
+        rec,
+        "m: {{a: 1, b: true}: 1, {a: 1, b: false}: 2}",
+        &hex!("0200000000000000 010101 010002"),
+    );
+
+    // Tuple keys
+    test_compile_error("Map<(u8, bool), u8>", "{(1, true): 1, (1, true): 2}");
+    test_compile(
+        "Map<(u8, bool), u8>",
+        "{(1, true): 1, (1, false): 2}",
+        &hex!("0200000000000000 010101 010002"),
+    );
+
+    // Invalid keys are not reported as duplicates
+    assert_eq!(
+        compile_diagnostics("Map<u8, u8>", "{1000: 1, 1000: 2}").len(),
+        2
+    );
+}
+
+#[test]
+fn test_map_invalid() {
+    test_compile_error("Map<u8, u8>", "[1, 2]");
+    test_compile_error("Map<u8, u8>", "1");
+}
+
+#[test]
+fn test_map_type_expressions() {
+    test_compile_ser(
+        "Map<u8, (i32, i32)>",
+        "{1: (-1, 2)}",
+        Some(&hex!("0100000000000000 01 ffffffff02000000")),
+        [(1u8, (-1i32, 2i32))]
+            .iter()
+            .cloned()
+            .collect::<HashMap<u8, (i32, i32)>>(),
+    );
+    test_compile_ser(
+        "Map<String, List<Option<u8>>>",
+        "{\"a\": [None, Some(1)]}",
+        Some(&hex!(
+            "0100000000000000 010000000000000061 0200000000000000 00 0101"
+        )),
+        [("a".to_string(), vec![None, Some(1u8)])]
+            .iter()
+            .cloned()
+            .collect::<HashMap<String, Vec<Option<u8>>>>(),
+    );
+}
+
+#[test]
+fn test_sorted_map() {
+    // Unsorted maps keep the order of the data
+    test_compile(
+        "Map<u8, u8>",
+        "{2: 0, 1: 0}",
+        &hex!("0200000000000000 0200 0100"),
+    );
+
+    // Keys are sorted by value, not by binary representation
+    let rec = "SortedMap<i16, u8>";
+    let expected = hex!("0300000000000000 ffff02 010003 000101");
+    test_compile_ser(
+        rec,
+        "{256: 1, -1: 2, 1: 3}",
+        Some(&expected),
+        [(256, 1), (-1, 2), (1, 3)]
+            .iter()
+            .cloned()
+            .collect::<BTreeMap<i16, u8>>(),
+    );
+    test_compile(rec, "{1: 3, -1: 2, 256: 1}", &expected);
+
+    test_compile_ser(
+        "SortedMap<String, u8>",
+        r#"{"b": 1, "ab": 2}"#,
+        Some(&hex!(
+            "0200000000000000 02000000000000006162 02 010000000000000062 01"
+        )),
+        [("b".to_string(), 1), ("ab".to_string(), 2)]
+            .iter()
+            .cloned()
+            .collect::<BTreeMap<String, u8>>(),
+    );
+    test_compile_ser(
+        "SortedMap<(Option<u8>, bool), u8>",
+        "{(Some(0), false): 1, (None, true): 2, (Some(0), true): 3}",
+        Some(&hex!("0300000000000000 000102 01000001 01000103")),
+        [
+            ((Some(0), false), 1),
+            ((None, true), 2),
+            ((Some(0), true), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect::<BTreeMap<(Option<u8>, bool), u8>>(),
+    );
+}
+
+#[test]
+fn test_sorted_map_keys() {
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    enum Key {
+        Named(String),
+        Id(u32),
+        Default,
+    }
+
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq)]
+    struct Index {
+        entries: BTreeMap<Key, u8>,
+    }
+
+    test_compile_ser(
+        "struct { enum Key { Named(String), Id(u32), Default }, entries: SortedMap<Key, u8> }",
+        r#"entries: {Default: 1, Id(300): 2, Named("a"): 3, Id(2): 4}"#,
+        Some(&hex!(
+            "0400000000000000
+            00000000 0100000000000000 61 03
+            01000000 02000000 04
+            01000000 2c010000 02
+            02000000 01"
+        )),
+        Index {
+            entries: vec![
+                (Key::Default, 1),
+                (Key::Id(300), 2),
+                (Key::Named("a".to_string()), 3),
+                (Key::Id(2), 4),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+}
+
+#[test]
+fn test_sorted_map_int_keys() {
+    // Integers which are not a whole number of bytes
+    test_compile(
+        "SortedMap<u12, u8>",
+        "{256: 1, 1: 2}",
+        &hex!("0200000000000000 0100 02 0001 01"),
+    );
+    test_compile(
+        "SortedMap<i12, u8>",
+        "{1: 1, -2048: 2, -1: 3}",
+        &hex!("0300000000000000 00f8 02 ffff 03 0100 01"),
+    );
+    test_compile(
+        "SortedMap<(u12, u8), u8>",
+        "{(256, 0): 1, (1, 5): 2}",
+        &hex!("0200000000000000 0100 05 02 0001 00 01"),
+    );
+}
+
+#[test]
+fn test_sort_maps_option() {
+    #[derive(Recipe, Deserialize)]
+    struct Table {
+        a: HashMap<u8, u8>,
+    }
+
+    let options = LoadOptions {
+        sort_maps: true,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    write_from_string_with_recipe_and_options(&mut out, "Map<u16, u8>", "{512: 1, 3: 2}", &options)
+        .unwrap();
+    assert_eq!(out, hex!("0200000000000000 0300 02 0002 01"));
+
+    // Deterministic output for HashMap based recipes
+    let mut outs = Vec::new();
+    for dat in ["a: {30: 3, 10: 1, 20: 2}", "a: {10: 1, 20: 2, 30: 3}"].iter() {
+        let value: Table = load_from_string_with_options(dat, &options).unwrap();
+        assert_eq!(value.a.len(), 3);
+        let mut out = Vec::new();
+        write_from_string_with_recipe_and_options(
+            &mut out,
+            "struct { a: Map<u8, u8> }",
+            dat,
+            &options,
+        )
+        .unwrap();
+        outs.push(out);
+    }
+    assert_eq!(outs[0], outs[1]);
+    assert_eq!(outs[0], hex!("0300000000000000 0a01 1402 1e03"));
+}
@@ -0,0 +1,145 @@
+use hex_literal::hex;
+mod common;
+use bakery::{load_from_string, write_from_string_with_recipe};
+use bakery_derive::Recipe;
+use common::test_compile_ser;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_option() {
+    let rec = "Option<u16>";
+    test_compile_ser(rec, "None", Some(&hex!("00")), None::<u16>);
+    test_compile_ser(rec, "Some(0)", Some(&hex!("010000")), Some(0u16));
+    test_compile_ser(rec, "Some(1234)", Some(&hex!("01d204")), Some(1234u16));
+
+    let rec = "Option<Option<u8>>";
+    test_compile_ser(rec, "None", Some(&hex!("00")), None::<Option<u8>>);
+    test_compile_ser(rec, "Some(None)", Some(&hex!("0100")), Some(None::<u8>));
+    test_compile_ser(rec, "Some(Some(3))", Some(&hex!("010103")), Some(Some(3u8)));
+
+    let rec = "Option<(u8, bool)>";
+    test_compile_ser(rec, "None", Some(&hex!("00")), None::<(u8, bool)>);
+    test_compile_ser(
+        rec,
+        "Some((2, true))",
+        Some(&hex!("010201")),
+        Some((2u8, true)),
+    );
+}
+
+#[test]
+fn test_option_invalid() {
+    let mut out = Vec::<u8>::new();
+    let rec = "Option<u8>";
+    assert!(write_from_string_with_recipe(&mut out, rec, "1").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "Some").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "Some(1, 2)").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "None(1)").is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, "Other(1)").is_err());
+}
+
+#[test]
+fn test_option_struct_member() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct S {
+        a: Option<u8>,
+        b: u8,
+    }
+
+    let rec = "struct { a: Option<u8>, b: u8 }";
+    test_compile_ser(
+        rec,
+        "a: Some(1), b: 2",
+        Some(&hex!("010102")),
+        S { a: Some(1), b: 2 },
+    );
+    test_compile_ser(
+        rec,
+        "a: None, b: 2",
+        Some(&hex!("0002")),
+        S { a: None, b: 2 },
+    );
+    // Optional members can be omitted
+    test_compile_ser(rec, "b: 2", Some(&hex!("0002")), S { a: None, b: 2 });
+    // Other members are still required
+    let mut out = Vec::<u8>::new();
+    assert!(write_from_string_with_recipe(&mut out, rec, "a: Some(1)").is_err());
+}
+
+#[test]
+fn test_option_generic() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Wrapper<T> {
+        value: Option<T>,
+        other: T,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct S {
+        a: Wrapper<u8>,
+        b: Wrapper<Option<u8>>,
+    }
+
+    let rec = "struct {
+        struct Wrapper<T> { value: Option<T>, other: T },
+        a: Wrapper<u8>,
+        b: Wrapper<Option<u8>>
+    }";
+    test_compile_ser(
+        rec,
+        "a: { value: Some(1), other: 2 }, b: { other: None }",
+        Some(&hex!("0101020000")),
+        S {
+            a: Wrapper {
+                value: Some(1),
+                other: 2,
+            },
+            b: Wrapper {
+                value: None,
+                other: None,
+            },
+        },
+    );
+    // A generic member whose type argument is an Option can be omitted too
+    assert_eq!(
+        load_from_string::<S>("a: { other: 2 }, b: { value: Some(None) }").unwrap(),
+        S {
+            a: Wrapper {
+                value: None,
+                other: 2
+            },
+            b: Wrapper {
+                value: Some(None),
+                other: None
+            },
+        }
+    );
+}
+
+#[test]
+fn test_nested_generic_argument() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct B<U> {
+        x: U,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct A<T> {
+        b: B<T>,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct S {
+        a: A<u8>,
+    }
+
+    // Generic argument of B refers to the generic type of A
+    test_compile_ser(
+        "struct { struct B<U> { x: U }, struct A<T> { b: B<T> }, a: A<u8> }",
+        "a: { b: { x: 5 } }",
+        Some(&hex!("05")),
+        S {
+            a: A { b: B { x: 5 } },
+        },
+    );
+}
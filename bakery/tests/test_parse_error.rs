@@ -0,0 +1,81 @@
+use bakery::{load_from_string, write_from_string_with_recipe, LoadError, ParseError};
+use std::collections::HashMap;
+
+/// Compile data with a recipe and return the recipe parse error.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+fn recipe_parse_error(rec: &str, dat: &str) -> ParseError {
+    let mut out = Vec::<u8>::new();
+    match write_from_string_with_recipe(&mut out, rec, dat) {
+        Err(LoadError::RecipeParseError(e)) => e,
+        r => panic!("expected recipe parse error, got {:?}", r),
+    }
+}
+
+/// Compile data with a recipe and return the data parse error.
+///
+/// # Arguments
+///
+/// * `rec` - Recipe string
+/// * `dat` - Data string
+fn data_parse_error(rec: &str, dat: &str) -> ParseError {
+    let mut out = Vec::<u8>::new();
+    match write_from_string_with_recipe(&mut out, rec, dat) {
+        Err(LoadError::DataParseError(e)) => e,
+        r => panic!("expected data parse error, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_recipe_parse_error() {
+    let e = recipe_parse_error("struct {\n    a: u8,\n    b u8\n}", "a: 1, b: 2");
+    assert_eq!(e.path(), None);
+    assert_eq!((e.line(), e.column()), (3, 5));
+    assert_eq!(e.snippet(), "    b u8");
+
+    // Garbage after the recipe
+    let e = recipe_parse_error("u8 u8", "1");
+    assert_eq!((e.line(), e.column()), (1, 4));
+    assert_eq!(e.expected(), ["end of input", "generic arguments"]);
+    assert_eq!(
+        e.to_string(),
+        "<string>:1:4: syntax error, expected end of input or generic arguments\n  | u8 u8\n  |    ^"
+    );
+
+    // Leading whitespaces are kept for line and column numbers
+    let e = recipe_parse_error("\n\n\tstruct {", "");
+    assert_eq!((e.line(), e.column()), (3, 10));
+}
+
+#[test]
+fn test_data_parse_error() {
+    let e = data_parse_error("struct { a: u8, b: u8 }", "a: 1,\nb: ");
+    assert_eq!((e.line(), e.column()), (2, 4));
+    assert!(e.expected().contains(&"integer".to_string()));
+
+    // Invalid escape sequence
+    let e = data_parse_error("String", r#""a\qb""#);
+    assert_eq!((e.line(), e.column()), (1, 1));
+
+    // Unterminated list
+    let e = data_parse_error("List<u8>", "[1, 2");
+    assert_eq!((e.line(), e.column()), (1, 5));
+
+    // With recipe built using Recipe trait
+    match load_from_string::<HashMap<u8, u8>>("{ 1: 2, 3 }") {
+        Err(LoadError::DataParseError(e)) => assert_eq!((e.line(), e.column()), (1, 9)),
+        r => panic!("expected data parse error, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_array_size_overflow() {
+    let mut out = Vec::<u8>::new();
+    assert!(matches!(
+        write_from_string_with_recipe(&mut out, "[u8; 99999999999999999999999]", "[1]"),
+        Err(LoadError::CompilationErrors(_))
+    ));
+}
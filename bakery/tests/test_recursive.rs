@@ -0,0 +1,192 @@
+use hex_literal::hex;
+mod common;
+use bakery::load_from_string;
+use bakery_derive::Recipe;
+use common::{test_compile, test_compile_ser};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_recursive_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct TreeNode {
+        value: u8,
+        children: Vec<TreeNode>,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Scene {
+        root: TreeNode,
+    }
+
+    test_compile_ser(
+        "struct {
+            struct TreeNode { value: u8, children: List<TreeNode> },
+            root: TreeNode
+        }",
+        "root: { value: 1, children: [{ value: 2, children: [] }, { value: 3, children: [] }] }",
+        Some(&hex!(
+            "01 0200000000000000 02 0000000000000000 03 0000000000000000"
+        )),
+        Scene {
+            root: TreeNode {
+                value: 1,
+                children: vec![
+                    TreeNode {
+                        value: 2,
+                        children: Vec::new(),
+                    },
+                    TreeNode {
+                        value: 3,
+                        children: Vec::new(),
+                    },
+                ],
+            },
+        },
+    );
+}
+
+#[test]
+fn test_recursive_enum() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum Expr {
+        Num(i8),
+        Add(Box<Expr>, Box<Expr>),
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Formula {
+        expr: Expr,
+    }
+
+    test_compile_ser(
+        "struct {
+            enum Expr { Num(i8), Add(Box<Expr>, Box<Expr>) },
+            expr: Expr
+        }",
+        "expr: Add(Num(1), Add(Num(2), Num(-3)))",
+        Some(&hex!(
+            "01000000 00000000 01 01000000 00000000 02 00000000 fd"
+        )),
+        Formula {
+            expr: Expr::Add(
+                Box::new(Expr::Num(1)),
+                Box::new(Expr::Add(Box::new(Expr::Num(2)), Box::new(Expr::Num(-3)))),
+            ),
+        },
+    );
+}
+
+#[test]
+fn test_mutually_recursive() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Folder {
+        files: Vec<File>,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct File {
+        size: u8,
+        link: Option<Box<Folder>>,
+    }
+
+    let folder: Folder =
+        load_from_string("files: [{ size: 1 }, { size: 2, link: Some({ files: [{ size: 3 }] }) }]")
+            .unwrap();
+    assert_eq!(
+        folder,
+        Folder {
+            files: vec![
+                File {
+                    size: 1,
+                    link: None
+                },
+                File {
+                    size: 2,
+                    link: Some(Box::new(Folder {
+                        files: vec![File {
+                            size: 3,
+                            link: None
+                        }]
+                    }))
+                }
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_recursive_generic() {
+    let rec = "struct {
+        struct Tree<T> { value: T, children: List<Tree<T>> },
+        t: Tree<u16>
+    }";
+    test_compile(
+        rec,
+        "t: { value: 1, children: [{ value: 2, children: [] }] }",
+        &hex!("0100 0100000000000000 0200 0000000000000000"),
+    );
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Tree<T> {
+        value: T,
+        children: Vec<Tree<T>>,
+    }
+
+    // Instantiations with different arguments are different types
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Forest {
+        a: Tree<u8>,
+        b: Tree<u16>,
+    }
+
+    let forest: Forest = load_from_string(
+        "a: { value: 1, children: [] }, b: { value: 1000, children: [{ value: 2, children: [] }] }",
+    )
+    .unwrap();
+    assert_eq!(forest.b.value, 1000);
+    assert_eq!(forest.b.children[0].value, 2);
+}
+
+#[test]
+fn test_recursive_newtype() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Children(Vec<Children>);
+
+    test_compile_ser(
+        "List<List<List<u8>>>",
+        "[[], [[]]]",
+        Some(&hex!(
+            "0200000000000000 0000000000000000 0100000000000000 0000000000000000"
+        )),
+        Children(vec![Children(vec![]), Children(vec![Children(vec![])])]),
+    );
+}
+
+#[test]
+fn test_registered_types_with_same_name() {
+    use bakery::{NodeContent, NodeTree, Recipe};
+
+    // Both types are named `test_recursive::test_registered_types_with_same_name::Item`, they
+    // must still be registered separately.
+    let mut tree = NodeTree::new();
+    let first = {
+        #[derive(Recipe)]
+        #[allow(dead_code)]
+        struct Item {
+            x: u8,
+        }
+        Item::recipe(&mut tree)
+    };
+    let second = {
+        #[derive(Recipe)]
+        #[allow(dead_code)]
+        struct Item {
+            x: u8,
+            y: u16,
+        }
+        Item::recipe(&mut tree)
+    };
+    assert_ne!(first, second);
+    assert!(matches!(tree.get(second).content, NodeContent::RecStruct));
+    assert_eq!(tree.children(second).len(), 2);
+}
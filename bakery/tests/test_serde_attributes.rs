@@ -0,0 +1,227 @@
+use hex_literal::hex;
+mod common;
+use bakery::load_from_string;
+use bakery_derive::Recipe;
+use common::test_compile_ser;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_rename() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Window {
+        max_width: u16,
+        #[serde(rename = "h")]
+        height: u16,
+        #[serde(rename(serialize = "ignored", deserialize = "titleBar"))]
+        decorated: bool,
+    }
+
+    test_compile_ser(
+        "struct { maxWidth: u16, h: u16, titleBar: bool }",
+        "maxWidth: 640, h: 480, titleBar: true",
+        Some(&hex!("8002 e001 01")),
+        Window {
+            max_width: 640,
+            height: 480,
+            decorated: true,
+        },
+    );
+    // Original field names are not members anymore
+    assert!(load_from_string::<Window>("maxWidth: 640, height: 480, decorated: true").is_err());
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        FullScreen,
+        #[serde(rename = "win")]
+        Windowed {
+            #[serde(rename = "w")]
+            width: u16,
+        },
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Display {
+        a: Mode,
+        b: Mode,
+    }
+
+    test_compile_ser(
+        "struct { a: enum { fullscreen, win { w: u16 } }, b: enum { fullscreen, win { w: u16 } } }",
+        "a: fullscreen, b: win { w: 800 }",
+        Some(&hex!("00000000 01000000 2003")),
+        Display {
+            a: Mode::FullScreen,
+            b: Mode::Windowed { width: 800 },
+        },
+    );
+}
+
+#[test]
+fn test_skip() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Sprite {
+        x: u8,
+        #[serde(skip)]
+        cache: Vec<u8>,
+        #[serde(skip_deserializing)]
+        dirty: bool,
+        y: u8,
+    }
+
+    let sprite: Sprite = load_from_string("x: 1, y: 2").unwrap();
+    assert_eq!(
+        sprite,
+        Sprite {
+            x: 1,
+            cache: Vec::new(),
+            dirty: false,
+            y: 2
+        }
+    );
+    // Skipped fields cannot be defined in data
+    assert!(load_from_string::<Sprite>("x: 1, y: 2, dirty: true").is_err());
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Circle,
+        #[serde(skip)]
+        #[allow(dead_code)]
+        Internal,
+        Square,
+    }
+
+    // Serde does not count skipped variants when deserializing, but does when serializing, so
+    // only deserialization is checked.
+    let shape: Shape = load_from_string("Square").unwrap();
+    assert_eq!(shape, Shape::Square);
+    assert_eq!(
+        bincode::deserialize::<Shape>(&hex!("01000000")).unwrap(),
+        Shape::Square
+    );
+    assert!(load_from_string::<Shape>("Internal").is_err());
+
+    // Unnamed fields can be skipped too
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Span(u8, #[serde(skip)] Vec<u8>, #[serde(skip)] u16);
+
+    test_compile_ser("(u8)", "(1)", Some(&hex!("01")), Span(1, Vec::new(), 0));
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    enum Message {
+        Move(u8, #[serde(skip)] bool, u16),
+    }
+
+    test_compile_ser(
+        "enum { Move(u8, u16) }",
+        "Move(1, 2)",
+        Some(&hex!("00000000 01 0200")),
+        Message::Move(1, false, 2),
+    );
+}
+
+fn default_volume() -> f32 {
+    0.8
+}
+
+#[test]
+fn test_default() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize, Default)]
+    struct Position {
+        x: u8,
+        y: u8,
+    }
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Sound {
+        #[serde(default = "default_volume")]
+        volume: f32,
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        position: Position,
+        channels: u8,
+    }
+
+    let rec = "struct {
+        struct Position { x: u8, y: u8 },
+        volume: f32 = 0.8,
+        name: String = \"\",
+        position: Position = { x: 0, y: 0 },
+        channels: u8
+    }";
+    test_compile_ser(
+        rec,
+        "channels: 2",
+        Some(&hex!("cdcc4c3f 0000000000000000 0000 02")),
+        Sound {
+            volume: 0.8,
+            name: String::new(),
+            position: Position { x: 0, y: 0 },
+            channels: 2,
+        },
+    );
+    // Defined values take precedence over defaults
+    test_compile_ser(
+        rec,
+        "channels: 2, name: \"a\", position: { x: 1, y: 2 }",
+        Some(&hex!("cdcc4c3f 010000000000000061 0102 02")),
+        Sound {
+            volume: 0.8,
+            name: "a".to_string(),
+            position: Position { x: 1, y: 2 },
+            channels: 2,
+        },
+    );
+    // Members without default are still required
+    assert!(load_from_string::<Sound>("").is_err());
+}
+
+#[test]
+fn test_container_default() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    struct Config {
+        width: u16,
+        height: u16,
+    }
+
+    impl Default for Config {
+        fn default() -> Self {
+            Config {
+                width: 640,
+                height: 480,
+            }
+        }
+    }
+
+    test_compile_ser(
+        "struct { width: u16 = 640, height: u16 = 480 }",
+        "height: 768",
+        Some(&hex!("8002 0003")),
+        Config {
+            width: 640,
+            height: 768,
+        },
+    );
+    assert_eq!(load_from_string::<Config>("").unwrap(), Config::default());
+}
+
+#[test]
+fn test_bakery_attributes() {
+    // Bakery attributes apply without serde attributes, and take precedence over them.
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    #[bakery(rename_all = "UPPERCASE")]
+    struct Color {
+        r: u8,
+        #[serde(rename = "green")]
+        #[bakery(rename = "G")]
+        g: u8,
+        #[bakery(default)]
+        b: u8,
+    }
+
+    let color: Color = load_from_string("R: 1, G: 2").unwrap();
+    assert_eq!(color, Color { r: 1, g: 2, b: 0 });
+}
@@ -0,0 +1,127 @@
+use hex_literal::hex;
+mod common;
+use bakery_derive::Recipe;
+use common::{compile_diagnostics, test_compile, test_compile_error, test_compile_ser};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::BuildHasherDefault;
+
+#[test]
+fn test_set() {
+    let rec = "Set<u8>";
+    test_compile_ser(
+        rec,
+        "{}",
+        Some(&hex!("0000000000000000")),
+        HashSet::<u8>::new(),
+    );
+    test_compile_ser(
+        rec,
+        "{1, 2}",
+        Some(&hex!("0200000000000000 01 02")),
+        [1, 2].iter().copied().collect::<BTreeSet<u8>>(),
+    );
+    // HashSet serialization ordering is not predictable
+    test_compile_ser(
+        rec,
+        "{1, 2, 3}",
+        None,
+        [1, 2, 3].iter().copied().collect::<HashSet<u8>>(),
+    );
+    test_compile_ser(
+        rec,
+        "{3}",
+        Some(&hex!("0100000000000000 03")),
+        [3].iter()
+            .copied()
+            .collect::<HashSet<u8, BuildHasherDefault<DefaultHasher>>>(),
+    );
+    test_compile_ser(
+        "Set<String>",
+        "{\"b\", \"a\"}",
+        Some(&hex!(
+            "0200000000000000 0100000000000000 61 0100000000000000 62"
+        )),
+        ["a", "b"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<String>>(),
+    );
+}
+
+#[test]
+fn test_set_sorted() {
+    // Output does not depend on the order of the items in the data
+    let rec = "Set<u8>";
+    test_compile(rec, "{3, 1, 2}", &hex!("0300000000000000 01 02 03"));
+    test_compile(rec, "{2, 3, 1}", &hex!("0300000000000000 01 02 03"));
+
+    // Items are sorted by value, not by binary representation
+    test_compile_ser(
+        "Set<i16>",
+        "{256, -1, 1}",
+        Some(&hex!("0300000000000000 ffff 0100 0001")),
+        [256, -1, 1].iter().copied().collect::<BTreeSet<i16>>(),
+    );
+    test_compile_ser(
+        "Set<String>",
+        r#"{"b", "ab"}"#,
+        Some(&hex!(
+            "0200000000000000 02000000000000006162 010000000000000062"
+        )),
+        ["b", "ab"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<String>>(),
+    );
+}
+
+#[test]
+fn test_set_errors() {
+    let rec = "Set<u8>";
+    test_compile_error(rec, "{1, 256}");
+    test_compile_error(rec, "{a: 1}");
+    let diagnostics = compile_diagnostics(rec, "[1, 2]");
+    assert_eq!(diagnostics[0].code(), "E0030");
+
+    // Duplicate items are detected using their value
+    let diagnostics = compile_diagnostics(rec, "{1, 2, 0x1}");
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(d.code(), "E0029");
+    assert_eq!(d.location().unwrap().column(), 8);
+    assert_eq!(d.related()[0].location().column(), 2);
+}
+
+#[test]
+fn test_set_tags() {
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+    enum Tag {
+        Flying,
+        Undead,
+        Boss,
+    }
+
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq)]
+    struct Monster {
+        tags: HashSet<Tag>,
+    }
+
+    test_compile_ser(
+        "struct { enum Tag { Flying, Undead, Boss }, tags: Set<Tag> }",
+        "tags: {Boss}",
+        Some(&hex!("0100000000000000 02000000")),
+        Monster {
+            tags: vec![Tag::Boss].into_iter().collect(),
+        },
+    );
+    test_compile_ser(
+        "struct { enum Tag { Flying, Undead, Boss }, tags: Set<Tag> }",
+        "tags: {Boss, Flying}",
+        None,
+        Monster {
+            tags: vec![Tag::Boss, Tag::Flying].into_iter().collect(),
+        },
+    );
+}
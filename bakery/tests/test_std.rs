@@ -0,0 +1,123 @@
+use bakery::load_from_string;
+use bakery_derive::Recipe;
+use hex_literal::hex;
+mod common;
+use common::{test_compile, test_compile_ser};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::BuildHasherDefault;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_vec_deque() {
+    test_compile_ser(
+        "List<u16>",
+        "[1, 2]",
+        Some(&hex!("0200000000000000 0100 0200")),
+        [1, 2].iter().copied().collect::<VecDeque<u16>>(),
+    );
+}
+
+#[test]
+fn test_btree_map() {
+    test_compile_ser(
+        "Map<u8, bool>",
+        "{1: true, 2: false}",
+        Some(&hex!("0200000000000000 01 01 02 00")),
+        [(1, true), (2, false)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<u8, bool>>(),
+    );
+    test_compile_ser(
+        "Map<u8, bool>",
+        "{1: true}",
+        Some(&hex!("0100000000000000 01 01")),
+        [(1, true)]
+            .iter()
+            .copied()
+            .collect::<HashMap<u8, bool, BuildHasherDefault<DefaultHasher>>>(),
+    );
+}
+
+#[test]
+fn test_smart_pointers() {
+    test_compile_ser("Rc<u8>", "1", Some(&hex!("01")), Rc::new(1u8));
+    test_compile_ser("Arc<u8>", "1", Some(&hex!("01")), Arc::new(1u8));
+    test_compile_ser(
+        "List<Arc<String>>",
+        "[\"a\"]",
+        Some(&hex!("0100000000000000 0100000000000000 61")),
+        vec![Arc::new("a".to_string())],
+    );
+}
+
+#[test]
+fn test_unit() {
+    test_compile_ser("()", "()", Some(&[]), ());
+    test_compile_ser("(u8, ())", "(1, ())", Some(&hex!("01")), (1u8, ()));
+    test_compile("struct { a: (), b: u8 }", "a: (), b: 2", &hex!("02"));
+}
+
+#[test]
+fn test_path() {
+    test_compile_ser(
+        "String",
+        "\"a/b\"",
+        Some(&hex!("0300000000000000 612f62")),
+        PathBuf::from("a/b"),
+    );
+}
+
+#[test]
+fn test_duration() {
+    #[derive(Recipe, Serialize, Deserialize, Debug, PartialEq)]
+    struct Timeout {
+        delay: Duration,
+    }
+
+    test_compile_ser(
+        "struct { delay: Duration }",
+        "delay: {secs: 2, nanos: 500}",
+        Some(&hex!("0200000000000000 f4010000")),
+        Timeout {
+            delay: Duration::new(2, 500),
+        },
+    );
+}
+
+#[test]
+fn test_large_tuple() {
+    // Tuples with more than 12 members implement neither Debug nor PartialEq
+    type T = (
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u16,
+    );
+    let dat = "(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)";
+    let bin = hex!("0102030405060708090a0b0c0d0e0f 1000");
+    test_compile(
+        "(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u16)",
+        dat,
+        &bin,
+    );
+    let value: T = load_from_string(dat).unwrap();
+    assert_eq!(bincode::serialize(&value).unwrap(), bin);
+}
@@ -0,0 +1,83 @@
+use hex_literal::hex;
+mod common;
+use bakery::write_from_string_with_recipe;
+use bakery_derive::Recipe;
+use common::test_compile_ser;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_string() {
+    let rec = "String";
+    test_compile_ser(rec, "\"\"", Some(&hex!("0000000000000000")), String::new());
+    test_compile_ser(
+        rec,
+        "\"abc\"",
+        Some(&hex!("0300000000000000616263")),
+        "abc".to_string(),
+    );
+    // Strings are encoded in UTF-8, length is in bytes.
+    test_compile_ser(
+        rec,
+        "\"é\"",
+        Some(&hex!("0200000000000000c3a9")),
+        "é".to_string(),
+    );
+    test_compile_ser(rec, "\"a, b: c\"", None, "a, b: c".to_string());
+    test_compile_ser(rec, "\"two\nlines\"", None, "two\nlines".to_string());
+}
+
+#[test]
+fn test_string_escapes() {
+    let rec = "String";
+    test_compile_ser(
+        rec,
+        r#""\n\r\t\0\\\"\'""#,
+        None,
+        "\n\r\t\0\\\"\'".to_string(),
+    );
+    test_compile_ser(
+        rec,
+        r#""\u{41}\u{e9}\u{1F600}""#,
+        Some(&hex!("0700000000000000""41c3a9f09f9880")),
+        "Aé😀".to_string(),
+    );
+    // Surrogates and values above 0x10ffff are not unicode scalar values.
+    let mut out = Vec::<u8>::new();
+    assert!(write_from_string_with_recipe(&mut out, rec, r#""\u{d800}""#).is_err());
+    assert!(write_from_string_with_recipe(&mut out, rec, r#""\u{110000}""#).is_err());
+}
+
+#[test]
+fn test_raw_string() {
+    let rec = "String";
+    test_compile_ser(rec, r#"r"a\nb""#, None, "a\\nb".to_string());
+    test_compile_ser(rec, r##"r#"say "hi""#"##, None, "say \"hi\"".to_string());
+    test_compile_ser(rec, r###"r##"a "# b"##"###, None, "a \"# b".to_string());
+}
+
+#[test]
+fn test_string_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        name: String,
+        path: String,
+        count: u8,
+    }
+
+    test_compile_ser(
+        "struct { name: String, path: String, count: u8 }",
+        "name: \"Sword\", path: r\"C:\\items\\sword.png\", count: 2",
+        None,
+        Item {
+            name: "Sword".to_string(),
+            path: "C:\\items\\sword.png".to_string(),
+            count: 2,
+        },
+    );
+    test_compile_ser(
+        "List<String>",
+        "[\"a\", \"bc\"]",
+        Some(&hex!("0200000000000000" "0100000000000000" "61" "0200000000000000" "6263")),
+        vec!["a".to_string(), "bc".to_string()],
+    );
+}
@@ -1,7 +1,12 @@
+use bakery::write_from_string_with_recipe_and_options;
+use bakery::{load_from_string, load_from_string_with_options, LoadError, LoadOptions};
 use hex_literal::hex;
 mod common;
 use bakery_derive::Recipe;
-use common::test_compile_ser;
+use common::{
+    compile_diagnostics, test_compile, test_compile_error, test_compile_error_code,
+    test_compile_ser,
+};
 use serde::{Deserialize, Serialize};
 
 #[test]
@@ -118,3 +123,186 @@ fn test_generic_struct_nested() {
         },
     );
 }
+
+#[test]
+fn test_struct_default() {
+    let rec = "struct { volume: f32 = 0.8, channels: u8 = 2, muted: bool = false }";
+    test_compile(rec, "", &hex!("cdcc4c3f 02 00"));
+    // Defined values take precedence over defaults
+    test_compile(rec, "channels: 6, muted: true", &hex!("cdcc4c3f 06 01"));
+}
+
+#[test]
+fn test_struct_default_aggregate() {
+    let rec = "struct {
+        struct Vector<T> { x: T = 1, y: T = 2 },
+        position: Vector<u8> = { x: 3, y: 4 },
+        scale: Vector<u8> = { },
+        tags: List<u8> = [],
+        pair: (u8, u8) = (5, 6),
+        kind: enum { A, B(u8) } = B(7),
+        name: String = \"none\"
+    }";
+    test_compile(
+        rec,
+        "",
+        &hex!("0304 0102 0000000000000000 0506 0100000007 0400000000000000 6e6f6e65"),
+    );
+    test_compile(
+        rec,
+        "position: { y: 9 }, kind: A",
+        &hex!("0109 0102 0000000000000000 0506 00000000 0400000000000000 6e6f6e65"),
+    );
+}
+
+#[test]
+fn test_struct_default_invalid() {
+    // Default values are type checked when used
+    test_compile("struct { a: u8 = 256 }", "a: 1", &hex!("01"));
+    test_compile_error("struct { a: u8 = 256 }", "");
+    test_compile_error("struct { a: u8 = true }", "");
+    // Members without default are still required
+    test_compile_error("struct { a: u8 = 1, b: u8 }", "a: 1");
+}
+
+#[test]
+fn test_struct_unknown_member() {
+    let rec = "struct { width: u16, height: u16, fullscreen: bool }";
+    let diagnostics = compile_diagnostics(rec, "widht: 1024, height: 768, fullscreen: true");
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].code(), "E0024");
+    assert!(diagnostics[0]
+        .message()
+        .starts_with("unknown member \"widht\""));
+    assert!(diagnostics[0]
+        .message()
+        .ends_with(", did you mean \"width\"?"));
+    // The misspelled member is missing as well
+    assert_eq!(diagnostics[1].code(), "E0021");
+
+    // Multiple suggestions, closest first
+    let diagnostics = compile_diagnostics(
+        "struct { abc: u8, abd: u8, ab: u8 }",
+        "abd: 1, ab: 2, abc: 3, abe: 4",
+    );
+    assert!(diagnostics[0]
+        .message()
+        .ends_with(", did you mean \"abc\", \"abd\" or \"ab\"?"));
+    // Suggestions are sorted by increasing distance, not by declaration order
+    let diagnostics = compile_diagnostics(
+        "struct { veloci: u8, speed: u8, velocit: u8, velocity: u8 }",
+        "veloci: 1, speed: 2, velocit: 3, velocity: 4, velocityx: 5",
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]
+        .message()
+        .ends_with(", did you mean \"velocity\", \"velocit\" or \"veloci\"?"));
+
+    // No suggestion if the name is too different
+    let diagnostics = compile_diagnostics("struct { width: u16 }", "width: 1, depth: 2");
+    assert!(!diagnostics[0].message().contains("did you mean"));
+
+    // Keys which are not identifiers are reported, and not checked as unknown members
+    test_compile_error_code("struct { a: u8 }", "5: 3", "E0014");
+    test_compile_error_code("struct { a: u8 }", "a: 1, \"a\": 3", "E0014");
+    test_compile_error_code("struct { a: u8 }", "a: 1, (b, c): 3", "E0014");
+
+    // Strict mode can be disabled
+    let options = LoadOptions {
+        allow_unknown_members: true,
+        ..Default::default()
+    };
+    let mut out = Vec::<u8>::new();
+    write_from_string_with_recipe_and_options(&mut out, "struct { a: u8 }", "a: 1, b: 2", &options)
+        .unwrap();
+    assert_eq!(out, hex!("01"));
+
+    // With recipe built using Recipe trait
+    #[derive(Recipe, Debug, PartialEq, Deserialize)]
+    struct Config {
+        width: u16,
+    }
+    assert!(matches!(
+        load_from_string::<Config>("width: 1, height: 2"),
+        Err(LoadError::CompilationErrors(_))
+    ));
+    assert_eq!(
+        load_from_string_with_options::<Config>("width: 1, height: 2", &options).unwrap(),
+        Config { width: 1 }
+    );
+}
+
+#[test]
+fn test_tuple_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Point(i16, u8);
+
+    test_compile_ser("(i16, u8)", "(-1, 2)", Some(&hex!("ffff02")), Point(-1, 2));
+
+    // Generic tuple structure
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Pair<T>(T, T);
+
+    test_compile_ser(
+        "(u16, u16)",
+        "(1, 2)",
+        Some(&hex!("01000200")),
+        Pair::<u16>(1, 2),
+    );
+}
+
+#[test]
+fn test_newtype_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Meters(f32);
+
+    test_compile_ser("f32", "1.5", Some(&hex!("0000c03f")), Meters(1.5));
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Wrapper<T>(T);
+
+    test_compile_ser(
+        "List<u8>",
+        "[1, 2]",
+        Some(&hex!("0200000000000000 01 02")),
+        Wrapper(vec![1u8, 2]),
+    );
+}
+
+#[test]
+fn test_unit_struct() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Marker;
+
+    test_compile_ser("struct { }", "", Some(&hex!("")), Marker);
+}
+
+#[test]
+fn test_struct_with_tuple_structs() {
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Point(u8, u8);
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Meters(u16);
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Marker;
+
+    #[derive(Recipe, Debug, PartialEq, Serialize, Deserialize)]
+    struct Shape {
+        origin: Point,
+        size: Meters,
+        marker: Marker,
+    }
+
+    test_compile_ser(
+        "struct { origin: (u8, u8), size: u16, marker: struct {} }",
+        "origin: (1, 2), size: 300, marker: {}",
+        Some(&hex!("0102 2c01")),
+        Shape {
+            origin: Point(1, 2),
+            size: Meters(300),
+            marker: Marker,
+        },
+    );
+}
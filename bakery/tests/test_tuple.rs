@@ -1,6 +1,6 @@
 use hex_literal::hex;
 mod common;
-use common::{test_compile_ser, test_load_from_string};
+use common::{test_compile, test_compile_error_code, test_compile_ser, test_load_from_string};
 
 #[test]
 fn test_tuple() {
@@ -67,3 +67,38 @@ fn test_tuple() {
 
     test_load_from_string("(99, false, (-1, 4))", (99, false, (-1, 4)));
 }
+
+#[test]
+fn test_tuple_type_expressions() {
+    test_compile_ser(
+        "(List<u8>, u8)",
+        "([1, 2], 3)",
+        Some(&hex!("0200000000000000 0102 03")),
+        (vec![1u8, 2], 3u8),
+    );
+    test_compile_ser(
+        "((u8, i8), [u16; 2], Option<bool>)",
+        "((1, -1), [2, 3], Some(true))",
+        Some(&hex!("01ff 02000300 0101")),
+        ((1u8, -1i8), [2u16, 3], Some(true)),
+    );
+    // Anonymous structures and enumerations
+    test_compile(
+        "(struct { a: u8 }, enum: u8 { A, B })",
+        "({ a: 1 }, B)",
+        &hex!("01 01"),
+    );
+    // Types declared in the enclosing structure
+    test_compile(
+        "struct { struct P { x: u8 }, t: (P, List<P>) }",
+        "t: ({ x: 1 }, [{ x: 2 }])",
+        &hex!("01 0100000000000000 02"),
+    );
+}
+
+#[test]
+fn test_tuple_invalid_data() {
+    test_compile_error_code("(u8, u8)", "[1, 2]", "E0032");
+    test_compile_error_code("(u8, u8)", "5", "E0032");
+    test_compile_error_code("struct { a: (u8, u8) }", "a: { b: 1 }", "E0032");
+}